@@ -101,4 +101,109 @@ create table workspace_tag
     create_at    datetime    not null comment '创建时间',
     update_at    datetime    not null comment '修改时间'
 )
-    comment '标签';
\ No newline at end of file
+    comment '标签';
+
+create table datasource_connection
+(
+    id           bigint       not null comment 'pk'
+        primary key,
+    tenant_id    bigint       not null comment '租户Id',
+    workspace_id bigint       not null comment '工作空间ID',
+    conn_key     varchar(50)  not null comment '连接标识符',
+    name         varchar(50)  not null comment '连接名称',
+    conn_type    varchar(20)  null comment '数据库类型',
+    url          varchar(200) not null comment '连接地址',
+    username     varchar(50)  not null comment '用户名',
+    password     varchar(512) not null comment '密码（主密钥加密）',
+    create_at    datetime     not null comment '创建时间',
+    update_at    datetime     not null comment '修改时间',
+    constraint uk_datasource_connection_key
+        unique (tenant_id, workspace_id, conn_key)
+)
+    comment '数据库连接';
+
+create table app_run
+(
+    id            bigint       not null
+        primary key,
+    tenant_id     bigint       not null comment '租户ID',
+    workspace_id  bigint       not null comment '工作空间ID',
+    app_id        bigint       not null comment '应用ID',
+    version_id    bigint       not null comment '应用版本ID',
+    version       varchar(50)  not null comment '版本号',
+    status        varchar(20)  not null comment '运行状态：succeeded、failed、cancelled',
+    input         mediumtext   null comment '调用参数 JSON',
+    output        mediumtext   null comment '结束节点输出变量 JSON',
+    error_code    int          null comment '错误码',
+    error_message text         null comment '错误信息',
+    caller_type   varchar(20)  not null comment '调用方类型',
+    caller_id     bigint       not null comment '调用方ID',
+    elapsed       bigint       not null comment '耗时（毫秒）',
+    start_at      datetime(3)  not null comment '开始时间',
+    finish_at     datetime(3)  not null comment '结束时间'
+)
+    comment '运行记录';
+
+create index idx_app_run_app_start
+    on app_run (tenant_id, workspace_id, app_id, start_at);
+
+create index idx_app_run_start_at
+    on app_run (start_at);
+
+create table app_run_node
+(
+    run_id    bigint       not null comment '运行记录ID',
+    seq       int          not null comment '节点开始执行的顺序',
+    node_id   varchar(100) not null comment '节点ID',
+    node_type varchar(20)  not null comment '节点类型',
+    input     mediumtext   null comment '节点输入 JSON',
+    output    mediumtext   null comment '节点输出 JSON',
+    elapsed   bigint       not null comment '耗时（毫秒）',
+    error     text         null comment '错误信息',
+    logs      mediumtext   null comment '调试日志 JSON',
+    create_at datetime     not null comment '创建时间',
+    primary key (run_id, seq)
+)
+    comment '运行记录的节点执行记录';
+
+create index idx_app_run_node_create_at
+    on app_run_node (create_at);
+
+create table app_version_log
+(
+    id                  bigint           not null
+        primary key,
+    tenant_id           bigint           not null comment '租户ID',
+    workspace_id        bigint           not null comment '工作空间ID',
+    app_id              bigint           not null comment '应用ID',
+    version_id          bigint           not null comment '应用版本ID',
+    version             varchar(50)      not null comment '版本号',
+    action              varchar(20)      not null comment '操作：restore-恢复为草稿，promote-设为最新版本',
+    previous_version_id bigint default 0 not null comment '操作前的最新版本ID',
+    user_id             bigint           not null comment '操作人',
+    create_at           datetime         not null comment '操作时间'
+)
+    comment '应用版本回滚记录';
+
+create index idx_app_version_log_app_id
+    on app_version_log (tenant_id, workspace_id, app_id);
+
+create table api_key
+(
+    id           bigint      not null
+        primary key,
+    tenant_id    bigint      not null comment '租户ID',
+    workspace_id bigint      not null comment '工作空间ID',
+    app_id       bigint      not null comment '应用ID，0 表示工作空间内的所有应用',
+    name         varchar(50) not null comment '密钥名称',
+    key_hash     char(64)    not null comment '密钥的 SHA-256 摘要',
+    key_hint     varchar(20) not null comment '密钥开头，用于列表展示',
+    create_by    bigint      not null comment '创建人',
+    create_at    datetime    not null comment '创建时间',
+    constraint uk_api_key_hash
+        unique (key_hash)
+)
+    comment 'API 密钥';
+
+create index idx_api_key_workspace
+    on api_key (tenant_id, workspace_id, app_id);
@@ -0,0 +1,45 @@
+use uorm::{Param, sql};
+
+#[derive(Param)]
+pub struct ApiKey {
+    pub id: u64,
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    // 只能调用该应用，为 0 时可调用工作空间内的所有应用
+    pub app_id: u64,
+    pub name: String,
+    // 密钥的 SHA-256 摘要，明文只在创建时返回
+    pub key_hash: String,
+    pub key_hint: String,
+    pub create_by: u64,
+    pub create_time: Option<String>,
+}
+
+#[sql("api_key")]
+pub struct ApiKeyDao;
+
+impl ApiKeyDao {
+    #[sql("list")]
+    pub async fn list(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: Option<u64>,
+    ) -> uorm::Result<Vec<ApiKey>> {
+        exec!()
+    }
+
+    #[sql("getByHash")]
+    pub async fn get_by_hash(key_hash: &str) -> uorm::Result<Option<ApiKey>> {
+        exec!()
+    }
+
+    #[sql("insert")]
+    pub async fn insert(key: &ApiKey) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("delete")]
+    pub async fn delete(tenant_id: u64, workspace_id: u64, id: u64) -> uorm::Result<u64> {
+        exec!()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::business::api_key::api_key_dao::{ApiKey, ApiKeyDao};
+use crate::business::app::app_dao::AppDao;
+use crate::core::code::Code;
+use crate::models::api_key::{ApiKeyCreateReq, ApiKeyCreateResp, ApiKeyReq, ApiKeyResp};
+use crate::models::context::Context;
+use crate::r;
+use crate::utils::api_key;
+use crate::utils::id::Id;
+use crate::web::error::WebError;
+use crate::web::extract::Json;
+use crate::web::r::R;
+use axum::extract::{Path, Query};
+use validator::Validate;
+
+// 查询 API 密钥列表
+pub async fn list_api_keys(ctx: Context, Query(req): Query<ApiKeyReq>) -> R<Vec<ApiKeyResp>> {
+    let keys = r!(ApiKeyDao::list(ctx.tenant_id, ctx.workspace_id, req.app_id).await);
+    R::ok(keys.into_iter().map(Into::into).collect())
+}
+
+// 创建 API 密钥
+pub async fn create_api_key(ctx: Context, Json(req): Json<ApiKeyCreateReq>) -> R<ApiKeyCreateResp> {
+    r!(req.validate());
+
+    let app_id = req.app_id.unwrap_or_default();
+    if app_id != 0 {
+        let app = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, app_id).await);
+        if app.is_none() {
+            return R::err(WebError::Biz(Code::AppNotExist.into()));
+        }
+    }
+
+    let key = api_key::generate();
+    let api_key = ApiKey {
+        id: r!(Id::next_id()),
+        tenant_id: ctx.tenant_id,
+        workspace_id: ctx.workspace_id,
+        app_id,
+        name: req.name,
+        key_hash: api_key::hash(&key),
+        key_hint: api_key::hint(&key),
+        create_by: ctx.user_id,
+        create_time: None,
+    };
+    r!(ApiKeyDao::insert(&api_key).await);
+    R::ok(ApiKeyCreateResp {
+        api_key: api_key.into(),
+        key,
+    })
+}
+
+// 吊销 API 密钥，立即失效
+pub async fn delete_api_key(ctx: Context, Path(id): Path<u64>) -> R<()> {
+    let rows = r!(ApiKeyDao::delete(ctx.tenant_id, ctx.workspace_id, id).await);
+    if rows == 0 {
+        return R::err(WebError::Biz(Code::ApiKeyNotExist.into()));
+    }
+    R::void()
+}
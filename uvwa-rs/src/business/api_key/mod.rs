@@ -0,0 +1,2 @@
+pub mod api_key_dao;
+pub mod api_key_handler;
@@ -27,6 +27,23 @@ pub struct AppVersion {
     pub spec: Option<String>,
     pub description: Option<String>,
     pub is_latest: bool,
+    pub create_time: Option<String>,
+}
+
+// 版本回滚的审计记录
+#[derive(Param)]
+pub struct AppVersionLog {
+    pub id: u64,
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    pub app_id: u64,
+    pub version_id: u64,
+    pub version: String,
+    pub action: String,
+    // 设为最新版本之前的最新版本，没有时为 0
+    pub previous_version_id: u64,
+    pub user_id: u64,
+    pub create_time: Option<String>,
 }
 
 #[sql("app")]
@@ -92,13 +109,14 @@ impl AppDao {
         exec!()
     }
 
+    // 返回影响的行数
     #[sql("updateSpec")]
     pub async fn update_spec(
         tenant_id: u64,
         workspace_id: u64,
         app_id: u64,
         spec: &String,
-    ) -> uorm::Result<()> {
+    ) -> uorm::Result<u64> {
         exec!()
     }
 
@@ -107,11 +125,59 @@ impl AppDao {
         exec!()
     }
 
+    #[sql("setLatest")]
+    pub async fn set_latest(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+        id: u64,
+    ) -> uorm::Result<u64> {
+        exec!()
+    }
+
     #[sql("insertVersion")]
     pub async fn insert_version(version: &AppVersion) -> uorm::Result<u64> {
         exec!()
     }
 
+    #[sql("getVersion")]
+    pub async fn get_version(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+        id: u64,
+    ) -> uorm::Result<Option<AppVersion>> {
+        exec!()
+    }
+
+    #[sql("listVersions")]
+    pub async fn list_versions(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+    ) -> uorm::Result<Vec<AppVersion>> {
+        exec!()
+    }
+
+    #[sql("getLatestVersion")]
+    pub async fn get_latest_version(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+    ) -> uorm::Result<Option<AppVersion>> {
+        exec!()
+    }
+
+    #[sql("getVersionByName")]
+    pub async fn get_version_by_name(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+        version: &str,
+    ) -> uorm::Result<Option<AppVersion>> {
+        exec!()
+    }
+
     #[sql("deleteVersions")]
     pub async fn delete_versions(
         tenant_id: u64,
@@ -120,4 +186,18 @@ impl AppDao {
     ) -> uorm::Result<()> {
         exec!()
     }
+
+    #[sql("insertVersionLog")]
+    pub async fn insert_version_log(log: &AppVersionLog) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("listVersionLogs")]
+    pub async fn list_version_logs(
+        tenant_id: u64,
+        workspace_id: u64,
+        app_id: u64,
+    ) -> uorm::Result<Vec<AppVersionLog>> {
+        exec!()
+    }
 }
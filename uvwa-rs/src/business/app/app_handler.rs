@@ -1,19 +1,27 @@
-use crate::business::app::app_dao::{App, AppDao};
+use crate::business::app::app_dao::{App, AppDao, AppVersion, AppVersionLog};
+use crate::business::app::subflow::WorkspaceFlowLoader;
+use crate::business::app::version::next_version;
 use crate::business::workspace_folder::folder_dao::FolderDao;
 use crate::core::code::Code;
 use crate::models::app::{
     AppCloneReq, AppCreateReq, AppReq, AppResp, AppSpecUpdateReq, AppTagUpdateReq, AppUpdateReq,
-    AppVersionReq,
+    AppVersionDiffQuery, AppVersionLogResp, AppVersionReq, AppVersionResp, VersionAction,
 };
 use crate::models::context::Context;
+use crate::models::spec::diff::{self, SpecDiff};
+use crate::models::spec::graph::SpecGraph;
+use crate::models::spec::scope::ScopeVar;
+use crate::models::spec::{AppSpec, SpecIssue};
 use crate::r;
 use crate::utils::id::Id;
+use crate::utils::semver::Version;
 use crate::web::error::WebError;
 use crate::web::extract::Json;
 use crate::web::r::R;
 use axum::extract::Path;
 use axum_extra::extract::Query;
 use serde_json;
+use std::cmp::Reverse;
 use uorm::transaction;
 use validator::Validate;
 
@@ -79,14 +87,45 @@ pub async fn update_app_spec(
     ctx: Context,
     Path(id): Path<u64>,
     Json(req): Json<AppSpecUpdateReq>,
-) -> R<()> {
+) -> R<Vec<SpecIssue>> {
     let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
     if exist.is_none() {
         return R::err(WebError::Biz(Code::AppNotExist.into()));
     }
 
-    r!(AppDao::update_spec(ctx.tenant_id, ctx.workspace_id, id, &req.spec).await);
-    R::void()
+    // 草稿无法解析时拒绝保存，并返回逐个节点的错误
+    let spec = match AppSpec::parse(&req.spec) {
+        Ok(spec) => spec,
+        Err(issues) => {
+            return R::err_with_data(WebError::Biz(Code::AppSpecInvalid.into()), issues);
+        }
+    };
+
+    r!(save_spec(&ctx, id, &req.spec).await);
+
+    // 编辑过程中图结构往往不完整，结构问题仅作为提示返回
+    R::ok(spec.lint())
+}
+
+// 草稿中节点可引用的变量，编辑器的变量选择以此为准
+pub async fn get_node_variables(
+    ctx: Context,
+    Path((id, node_id)): Path<(u64, String)>,
+) -> R<Vec<ScopeVar>> {
+    let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await);
+    let Some(spec) = spec else {
+        return R::err(WebError::Biz(Code::AppDraftNotExist.into()));
+    };
+    // 草稿无法解析时节点和连线都不可信，不返回部分结果
+    let Ok(spec) = AppSpec::parse(&spec) else {
+        return R::err(WebError::Biz(Code::AppSpecInvalid.into()));
+    };
+
+    let graph = SpecGraph::new(&spec);
+    if graph.node(&node_id).is_none() {
+        return R::err(WebError::Biz(Code::AppNodeNotExist.into()));
+    }
+    R::ok(graph.visible(&node_id))
 }
 
 pub async fn update_app_tags(
@@ -134,21 +173,181 @@ pub async fn release_app(
     ctx: Context,
     Path(id): Path<u64>,
     Json(req): Json<AppVersionReq>,
-) -> R<()> {
+) -> R<Vec<SpecIssue>> {
     let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
     if exist.is_none() {
         return R::err(WebError::Biz(Code::AppNotExist.into()));
     }
 
     let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await);
-    if spec.is_none() {
+    let Some(spec) = spec else {
         return R::err(WebError::Biz(Code::AppDraftNotExist.into()));
+    };
+
+    // 发布时结构问题同样阻断，引用的子流程需在当前工作空间内已发布
+    let issues = match AppSpec::parse(&spec) {
+        Ok(parsed) => {
+            let flows = WorkspaceFlowLoader {
+                tenant_id: ctx.tenant_id,
+                workspace_id: ctx.workspace_id,
+            };
+            let mut issues = parsed.lint();
+            issues.extend(r!(flows.lint(id, &parsed).await));
+            issues
+        }
+        Err(issues) => issues,
+    };
+    if !issues.is_empty() {
+        return R::err_with_data(WebError::Biz(Code::AppSpecInvalid.into()), issues);
     }
 
+    let released = r!(AppDao::list_versions(ctx.tenant_id, ctx.workspace_id, id).await);
+    let version = r!(next_version(&released, &req));
+
     r!(AppDao::cancel_latest(ctx.tenant_id, ctx.workspace_id, id).await);
 
-    let version = (exist.unwrap(), req, spec.unwrap()).into();
+    let version = (exist.unwrap(), version, req, spec).into();
     r!(AppDao::insert_version(&version).await);
 
+    R::ok(vec![])
+}
+
+// 查询应用的版本列表，按版本号优先级从高到低，无法解析的版本号排在最后
+pub async fn list_app_versions(ctx: Context, Path(id): Path<u64>) -> R<Vec<AppVersionResp>> {
+    let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
+    if exist.is_none() {
+        return R::err(WebError::Biz(Code::AppNotExist.into()));
+    }
+
+    let mut versions = r!(AppDao::list_versions(ctx.tenant_id, ctx.workspace_id, id).await);
+    // 稳定排序，优先级相同的版本保持发布时间倒序
+    versions.sort_by_cached_key(|v| Reverse(Version::parse(&v.version)));
+    R::ok(versions.into_iter().map(Into::into).collect())
+}
+
+// 获取版本详情，包含发布时的草稿
+pub async fn get_app_version(
+    ctx: Context,
+    Path((id, version_id)): Path<(u64, u64)>,
+) -> R<AppVersionResp> {
+    let version = r!(AppDao::get_version(ctx.tenant_id, ctx.workspace_id, id, version_id).await);
+    match version {
+        Some(version) => R::ok(version.into()),
+        None => R::err(WebError::Biz(Code::AppVersionNotExist.into())),
+    }
+}
+
+// 对比版本与另一版本或当前草稿的节点和连线差异
+pub async fn diff_app_version(
+    ctx: Context,
+    Path((id, version_id)): Path<(u64, u64)>,
+    Query(query): Query<AppVersionDiffQuery>,
+) -> R<SpecDiff> {
+    let base = r!(version_spec(&ctx, id, version_id).await);
+    let target = match query.to {
+        Some(to) => r!(version_spec(&ctx, id, to).await),
+        None => {
+            let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await);
+            let Some(spec) = spec else {
+                return R::err(WebError::Biz(Code::AppDraftNotExist.into()));
+            };
+            spec
+        }
+    };
+
+    match diff::diff(&base, &target) {
+        Ok(diff) => R::ok(diff),
+        Err(_) => R::err(WebError::Biz(Code::AppSpecInvalid.into())),
+    }
+}
+
+async fn version_spec(ctx: &Context, id: u64, version_id: u64) -> Result<String, WebError> {
+    let version = AppDao::get_version(ctx.tenant_id, ctx.workspace_id, id, version_id).await?;
+    match version {
+        Some(version) => Ok(version.spec.unwrap_or_default()),
+        None => Err(WebError::Biz(Code::AppVersionNotExist.into())),
+    }
+}
+
+// 把版本发布时的草稿恢复为当前草稿，当前未发布的修改会被覆盖
+#[transaction]
+pub async fn restore_app_version(ctx: Context, Path((id, version_id)): Path<(u64, u64)>) -> R<()> {
+    let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
+    if exist.is_none() {
+        return R::err(WebError::Biz(Code::AppNotExist.into()));
+    }
+    let version = r!(AppDao::get_version(ctx.tenant_id, ctx.workspace_id, id, version_id).await);
+    let Some(version) = version else {
+        return R::err(WebError::Biz(Code::AppVersionNotExist.into()));
+    };
+
+    let spec = version.spec.clone().unwrap_or_default();
+    r!(save_spec(&ctx, id, &spec).await);
+    r!(insert_version_log(&ctx, &version, VersionAction::Restore, 0).await);
+    R::void()
+}
+
+// 把旧版本重新设为最新版本，按最新版本运行和引用的地方随之切换
+#[transaction]
+pub async fn promote_app_version(ctx: Context, Path((id, version_id)): Path<(u64, u64)>) -> R<()> {
+    let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
+    if exist.is_none() {
+        return R::err(WebError::Biz(Code::AppNotExist.into()));
+    }
+    let version = r!(AppDao::get_version(ctx.tenant_id, ctx.workspace_id, id, version_id).await);
+    let Some(version) = version else {
+        return R::err(WebError::Biz(Code::AppVersionNotExist.into()));
+    };
+    if version.is_latest {
+        return R::void();
+    }
+
+    let previous = r!(AppDao::get_latest_version(ctx.tenant_id, ctx.workspace_id, id).await);
+    r!(AppDao::cancel_latest(ctx.tenant_id, ctx.workspace_id, id).await);
+    r!(AppDao::set_latest(ctx.tenant_id, ctx.workspace_id, id, version_id).await);
+
+    let previous_id = previous.map(|v| v.id).unwrap_or_default();
+    r!(insert_version_log(&ctx, &version, VersionAction::Promote, previous_id).await);
     R::void()
 }
+
+// 查询应用的版本回滚记录
+pub async fn list_app_version_logs(ctx: Context, Path(id): Path<u64>) -> R<Vec<AppVersionLogResp>> {
+    let logs = r!(AppDao::list_version_logs(ctx.tenant_id, ctx.workspace_id, id).await);
+    R::ok(logs.into_iter().map(Into::into).collect())
+}
+
+// 保存草稿并确认更新命中了应用：MySQL 对内容未变化的行返回 0，
+// 此时读回草稿比较，仍不一致说明条件没有匹配到应用
+async fn save_spec(ctx: &Context, id: u64, spec: &String) -> Result<(), WebError> {
+    let updated = AppDao::update_spec(ctx.tenant_id, ctx.workspace_id, id, spec).await?;
+    if updated == 0 {
+        let saved = AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await?;
+        if saved.as_ref() != Some(spec) {
+            return Err(WebError::Biz(Code::AppNotExist.into()));
+        }
+    }
+    Ok(())
+}
+
+async fn insert_version_log(
+    ctx: &Context,
+    version: &AppVersion,
+    action: VersionAction,
+    previous_version_id: u64,
+) -> Result<(), WebError> {
+    let log = AppVersionLog {
+        id: Id::next_id()?,
+        tenant_id: ctx.tenant_id,
+        workspace_id: ctx.workspace_id,
+        app_id: version.app_id,
+        version_id: version.id,
+        version: version.version.clone(),
+        action: action.as_str().to_string(),
+        previous_version_id,
+        user_id: ctx.user_id,
+        create_time: None,
+    };
+    AppDao::insert_version_log(&log).await?;
+    Ok(())
+}
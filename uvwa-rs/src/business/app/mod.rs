@@ -1,2 +1,4 @@
 pub mod app_dao;
 pub mod app_handler;
+pub mod subflow;
+pub mod version;
@@ -0,0 +1,139 @@
+use crate::business::app::app_dao::AppVersion;
+use crate::core::code::Code;
+use crate::models::app::AppVersionReq;
+use crate::utils::semver::Version;
+use crate::web::error::WebError;
+
+// 与 app_version 表的 version、pre_release 字段长度一致
+const MAX_VERSION_LEN: usize = 50;
+const MAX_PRE_RELEASE_LEN: usize = 20;
+
+// 计算要发布的版本号：指定的版本号需合法且高于已发布的最高版本，自动递增从最高版本开始
+// 早期发布的无法解析的版本号不参与比较
+pub fn next_version(released: &[AppVersion], req: &AppVersionReq) -> Result<Version, WebError> {
+    let highest = released
+        .iter()
+        .filter_map(|v| Version::parse(&v.version))
+        .max();
+
+    let version = match (&req.version, req.bump) {
+        (Some(version), None) => Version::parse(version).ok_or_else(|| invalid(version))?,
+        (None, Some(bump)) => highest.clone().unwrap_or(Version::ZERO).bump(bump),
+        (Some(_), Some(_)) => return Err(illegal_param("bump")),
+        (None, None) => return Err(illegal_param("version")),
+    };
+    // 递增预发布号也可能超长
+    let text = version.to_string();
+    let pre_len = version.pre_release().map_or(0, |pre| pre.len());
+    if text.len() > MAX_VERSION_LEN || pre_len > MAX_PRE_RELEASE_LEN {
+        return Err(invalid(&text));
+    }
+
+    // 构建信息不同的同号版本也视为重复
+    let duplicate = released
+        .iter()
+        .any(|v| Version::parse(&v.version).as_ref() == Some(&version));
+    if duplicate {
+        return Err(WebError::BizWithArgs(
+            Code::AppVersionDuplicate.into(),
+            vec![("version".to_string(), version.to_string())],
+        ));
+    }
+    if let Some(highest) = highest
+        && version < highest
+    {
+        return Err(WebError::BizWithArgs(
+            Code::AppVersionRegression.into(),
+            vec![
+                ("version".to_string(), version.to_string()),
+                ("latest".to_string(), highest.to_string()),
+            ],
+        ));
+    }
+    Ok(version)
+}
+
+fn invalid(version: &str) -> WebError {
+    WebError::BizWithArgs(
+        Code::AppVersionInvalid.into(),
+        vec![("version".to_string(), version.to_string())],
+    )
+}
+
+fn illegal_param(field: &str) -> WebError {
+    WebError::BizWithArgs(
+        Code::IllegalParam.into(),
+        vec![("field".to_string(), field.to_string())],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::semver::Bump;
+
+    fn released(versions: &[&str]) -> Vec<AppVersion> {
+        versions
+            .iter()
+            .map(|v| AppVersion {
+                id: 1,
+                tenant_id: 1,
+                workspace_id: 1,
+                app_id: 1,
+                version: v.to_string(),
+                major: None,
+                minor: None,
+                patch: None,
+                pre_release: None,
+                spec: None,
+                description: None,
+                is_latest: false,
+                create_time: None,
+            })
+            .collect()
+    }
+
+    fn req(version: Option<&str>, bump: Option<Bump>) -> AppVersionReq {
+        AppVersionReq {
+            version: version.map(|v| v.to_string()),
+            bump,
+            description: None,
+        }
+    }
+
+    fn assert_code(result: Result<Version, WebError>, expected: Code) {
+        match result {
+            Err(WebError::BizWithArgs(code, _)) => assert_eq!(code, i32::from(expected)),
+            _ => panic!("expected a business error"),
+        }
+    }
+
+    #[test]
+    fn test_next_version() {
+        let versions = released(&["1.0.0", "1.2.0", "1.1.0", "v0.9"]);
+        let next = |version, bump| next_version(&versions, &req(version, bump));
+
+        assert_eq!(next(Some("1.2.1"), None).unwrap().to_string(), "1.2.1");
+        assert_eq!(next(None, Some(Bump::Minor)).unwrap().to_string(), "1.3.0");
+        assert_eq!(
+            next(None, Some(Bump::Prerelease)).unwrap().to_string(),
+            "1.2.1-0"
+        );
+
+        assert_code(next(Some("1.2"), None), Code::AppVersionInvalid);
+        assert_code(next(Some("1.2.0"), None), Code::AppVersionDuplicate);
+        assert_code(next(Some("1.2.0+build"), None), Code::AppVersionDuplicate);
+        assert_code(next(Some("1.1.5"), None), Code::AppVersionRegression);
+        assert_code(next(Some("1.2.0-rc.1"), None), Code::AppVersionRegression);
+        assert_code(next(None, None), Code::IllegalParam);
+        let long_pre = format!("1.2.1-{}", "a".repeat(21));
+        let long_build = format!("1.2.1+{}", "b".repeat(45));
+        assert_code(next(Some(&long_pre), None), Code::AppVersionInvalid);
+        assert_code(next(Some(&long_build), None), Code::AppVersionInvalid);
+        assert_code(next(Some("2.0.0"), Some(Bump::Major)), Code::IllegalParam);
+
+        // 首次发布从 0.0.0 开始递增
+        let first = next_version(&[], &req(None, Some(Bump::Patch))).unwrap();
+        assert_eq!(first.to_string(), "0.0.1");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::business::app_run::run_handler::engine;
+use crate::engine::debug::Debugger;
+use crate::engine::trace::NodeTrace;
+use crate::engine::vars::Scope;
+use crate::models::app_run::{AppRunResp, DebugCreateReq, DebugState, DebugStatus};
+use crate::models::context::Context;
+use crate::models::spec::{AppSpec, Node};
+use crate::web::r::R;
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// 单次请求等待暂停或结束的最长时间，超时后返回运行中的状态，之后可查询状态
+const WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 调试命令：单步执行到下一个节点前暂停，或继续执行到下一个断点
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DebugCommand {
+    Step,
+    Continue,
+}
+
+// 草稿的调试运行，运行任务在后台执行，暂停时等待调试命令
+pub struct DebugSession {
+    pub id: u64,
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    pub user_id: u64,
+    pub app_id: u64,
+    control: Arc<DebugControl>,
+    commands: UnboundedSender<DebugCommand>,
+    // 同一会话的命令依次处理，避免并发的命令跳过暂停点
+    resume: tokio::sync::Mutex<()>,
+    task: JoinHandle<()>,
+}
+
+impl DebugSession {
+    // 开始运行草稿，开启 pauseOnStart 时在第一个节点前暂停
+    pub fn start(id: u64, ctx: &Context, app_id: u64, spec: AppSpec, req: DebugCreateReq) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let control = Arc::new(DebugControl {
+            breakpoints: Mutex::new(req.breakpoints.into_iter().collect()),
+            stepping: AtomicBool::new(req.pause_on_start),
+            commands: tokio::sync::Mutex::new(receiver),
+            state: watch::Sender::new(DebugState::running()),
+        });
+
+        let task_ctx = ctx.clone();
+        let debugger = control.clone();
+        let task = tokio::spawn(async move {
+            let (result, trace) = engine(&task_ctx, app_id, &spec)
+                .debugger(debugger.clone())
+                .conversation(req.conversation)
+                .run_traced(req.input)
+                .await;
+            let state = match result {
+                Ok(output) => DebugState {
+                    status: DebugStatus::Succeeded,
+                    output: Some(AppRunResp::from(output)),
+                    trace,
+                    ..DebugState::running()
+                },
+                Err(err) => DebugState {
+                    status: DebugStatus::Failed,
+                    error: Some(R::err(err.into())),
+                    trace,
+                    ..DebugState::running()
+                },
+            };
+            debugger.state.send_replace(state);
+        });
+
+        Self {
+            id,
+            tenant_id: ctx.tenant_id,
+            workspace_id: ctx.workspace_id,
+            user_id: ctx.user_id,
+            app_id,
+            control,
+            commands,
+            resume: tokio::sync::Mutex::new(()),
+            task,
+        }
+    }
+
+    // 会话只对创建者可见
+    pub fn owned_by(&self, ctx: &Context) -> bool {
+        self.tenant_id == ctx.tenant_id
+            && self.workspace_id == ctx.workspace_id
+            && self.user_id == ctx.user_id
+    }
+
+    pub fn state(&self) -> DebugState {
+        self.control.state.borrow().clone()
+    }
+
+    pub fn breakpoints(&self) -> Vec<String> {
+        let mut breakpoints: Vec<_> = self
+            .control
+            .breakpoints
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        breakpoints.sort();
+        breakpoints
+    }
+
+    pub fn set_breakpoints(&self, breakpoints: Vec<String>) {
+        *self.control.breakpoints.lock().unwrap() = breakpoints.into_iter().collect();
+    }
+
+    // 等待运行暂停或结束
+    pub async fn wait(&self) -> DebugState {
+        let mut receiver = self.control.state.subscribe();
+        let _ = tokio::time::timeout(
+            WAIT_TIMEOUT,
+            receiver.wait_for(|s| s.status != DebugStatus::Running),
+        )
+        .await;
+        self.state()
+    }
+
+    // 暂停时发送调试命令并等待下一次暂停或结束；未暂停时直接返回当前状态
+    pub async fn resume(&self, command: DebugCommand) -> DebugState {
+        let _guard = self.resume.lock().await;
+        if self.control.state.borrow().status != DebugStatus::Paused {
+            return self.state();
+        }
+
+        // 先标记为运行中，等待的是命令之后的暂停
+        self.control.state.send_modify(|s| {
+            s.status = DebugStatus::Running;
+            s.node_id = None;
+        });
+        let _ = self.commands.send(command);
+        self.wait().await
+    }
+}
+
+// 会话被删除或过期时中止运行，进行中的事务随连接回滚
+impl Drop for DebugSession {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// 运行任务持有的调试控制，实现引擎的 Debugger
+struct DebugControl {
+    breakpoints: Mutex<HashSet<String>>,
+    // 单步执行：下一个节点前暂停
+    stepping: AtomicBool,
+    commands: tokio::sync::Mutex<UnboundedReceiver<DebugCommand>>,
+    state: watch::Sender<DebugState>,
+}
+
+#[async_trait]
+impl Debugger for DebugControl {
+    async fn before_node(&self, node: &Node, scope: &Scope, trace: &[NodeTrace]) {
+        let stepping = self.stepping.load(Ordering::Relaxed);
+        if !stepping && !self.breakpoints.lock().unwrap().contains(&node.id) {
+            return;
+        }
+
+        self.state.send_replace(DebugState {
+            status: DebugStatus::Paused,
+            node_id: Some(node.id.clone()),
+            scope: scope
+                .values()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+            trace: trace.to_vec(),
+            ..DebugState::running()
+        });
+        // 会话被丢弃时发送方关闭，继续执行直到任务被中止
+        let command = self.commands.lock().await.recv().await;
+        self.stepping
+            .store(command == Some(DebugCommand::Step), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn start(req: Value) -> DebugSession {
+        let ctx = Context {
+            tenant_id: 1,
+            user_id: 2,
+            workspace_id: 3,
+        };
+        let spec = AppSpec::parse(include_str!("../../engine/fixtures/linear.json")).unwrap();
+        let req = serde_json::from_value(req).unwrap();
+        DebugSession::start(1, &ctx, 10, spec, req)
+    }
+
+    #[tokio::test]
+    async fn test_debug_step() {
+        let session = start(json!({"input": {"name": "uvwa"}, "pauseOnStart": true}));
+        let state = session.wait().await;
+        assert_eq!(state.status, DebugStatus::Paused);
+        assert_eq!(state.node_id.as_deref(), Some("start"));
+
+        // 单步后暂停在下一个节点前，可以看到开始节点写入的变量
+        let state = session.resume(DebugCommand::Step).await;
+        assert_eq!(state.status, DebugStatus::Paused);
+        assert_eq!(state.node_id.as_deref(), Some("end"));
+        assert_eq!(state.scope["v_name"], json!("uvwa"));
+        assert_eq!(state.trace.len(), 1);
+
+        let state = session.resume(DebugCommand::Continue).await;
+        assert_eq!(state.status, DebugStatus::Succeeded);
+        assert_eq!(state.output.unwrap().text.as_deref(), Some("hello uvwa"));
+
+        // 结束后的命令不再生效
+        let state = session.resume(DebugCommand::Step).await;
+        assert_eq!(state.status, DebugStatus::Succeeded);
+    }
+
+    #[tokio::test]
+    async fn test_debug_breakpoints() {
+        let session = start(json!({"input": {"name": "uvwa"}, "breakpoints": ["end"]}));
+        let state = session.wait().await;
+        assert_eq!(state.node_id.as_deref(), Some("end"));
+
+        let state = session.resume(DebugCommand::Continue).await;
+        assert_eq!(state.status, DebugStatus::Succeeded);
+
+        // 输入校验失败时返回与运行接口相同的错误
+        let session = start(json!({"breakpoints": ["end"]}));
+        let state = session.wait().await;
+        assert_eq!(state.status, DebugStatus::Failed);
+        assert!(state.error.is_some());
+    }
+}
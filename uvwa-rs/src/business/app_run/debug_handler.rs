@@ -0,0 +1,113 @@
+use crate::business::app::app_dao::AppDao;
+use crate::business::app_run::debug::{DebugCommand, DebugSession};
+use crate::business::cache::debug_session::{get_session, remove_session, save_session};
+use crate::core::code::Code;
+use crate::models::app_run::{DebugBreakpointsReq, DebugCreateReq, DebugSessionResp, DebugState};
+use crate::models::context::Context;
+use crate::models::spec::AppSpec;
+use crate::models::spec::graph::SpecGraph;
+use crate::r;
+use crate::utils::id::Id;
+use crate::web::error::WebError;
+use crate::web::extract::Json;
+use crate::web::r::R;
+use axum::extract::Path;
+use std::sync::Arc;
+
+// 调试运行草稿，运行到第一个断点暂停或结束后返回
+pub async fn create_debug_session(
+    ctx: Context,
+    Path(id): Path<u64>,
+    Json(req): Json<DebugCreateReq>,
+) -> R<DebugSessionResp> {
+    let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await);
+    let Some(spec) = spec else {
+        return R::err(WebError::Biz(Code::AppDraftNotExist.into()));
+    };
+    let Ok(spec) = AppSpec::parse(&spec) else {
+        return R::err(WebError::Biz(Code::AppSpecInvalid.into()));
+    };
+    if let Err(err) = check_breakpoints(&spec, &req.breakpoints) {
+        return R::err(err);
+    }
+
+    let session_id = r!(Id::next_id());
+    let session = Arc::new(DebugSession::start(session_id, &ctx, id, spec, req));
+    save_session(session.clone()).await;
+    let state = session.wait().await;
+    R::ok(response(&session, state))
+}
+
+// 查询调试会话的当前状态
+pub async fn get_debug_session(ctx: Context, Path(id): Path<u64>) -> R<DebugSessionResp> {
+    let session = r!(owned_session(&ctx, id).await);
+    R::ok(response(&session, session.state()))
+}
+
+// 单步执行：执行暂停的节点，在下一个节点前再次暂停
+pub async fn step_debug_session(ctx: Context, Path(id): Path<u64>) -> R<DebugSessionResp> {
+    let session = r!(owned_session(&ctx, id).await);
+    let state = session.resume(DebugCommand::Step).await;
+    R::ok(response(&session, state))
+}
+
+// 继续执行到下一个断点或结束
+pub async fn continue_debug_session(ctx: Context, Path(id): Path<u64>) -> R<DebugSessionResp> {
+    let session = r!(owned_session(&ctx, id).await);
+    let state = session.resume(DebugCommand::Continue).await;
+    R::ok(response(&session, state))
+}
+
+// 替换断点，对之后执行的节点生效
+pub async fn update_debug_breakpoints(
+    ctx: Context,
+    Path(id): Path<u64>,
+    Json(req): Json<DebugBreakpointsReq>,
+) -> R<DebugSessionResp> {
+    let session = r!(owned_session(&ctx, id).await);
+    let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, session.app_id).await);
+    // 草稿在调试期间可能已修改，只校验仍能解析的草稿
+    if let Some(Ok(spec)) = spec.as_deref().map(AppSpec::parse)
+        && let Err(err) = check_breakpoints(&spec, &req.breakpoints)
+    {
+        return R::err(err);
+    }
+
+    session.set_breakpoints(req.breakpoints);
+    R::ok(response(&session, session.state()))
+}
+
+// 中止调试运行并删除会话
+pub async fn delete_debug_session(ctx: Context, Path(id): Path<u64>) -> R<()> {
+    let session = r!(owned_session(&ctx, id).await);
+    remove_session(session.id).await;
+    R::void()
+}
+
+// 其他用户的会话同样视为不存在
+async fn owned_session(ctx: &Context, id: u64) -> Result<Arc<DebugSession>, WebError> {
+    match get_session(id).await {
+        Some(session) if session.owned_by(ctx) => Ok(session),
+        _ => Err(WebError::Biz(Code::DebugSessionNotExist.into())),
+    }
+}
+
+fn check_breakpoints(spec: &AppSpec, breakpoints: &[String]) -> Result<(), WebError> {
+    let graph = SpecGraph::new(spec);
+    match breakpoints.iter().find(|id| graph.node(id).is_none()) {
+        Some(id) => Err(WebError::BizWithArgs(
+            Code::DebugBreakpointInvalid.into(),
+            vec![("node".to_string(), id.clone())],
+        )),
+        None => Ok(()),
+    }
+}
+
+fn response(session: &DebugSession, state: DebugState) -> DebugSessionResp {
+    DebugSessionResp {
+        id: session.id,
+        app_id: session.app_id,
+        breakpoints: session.breakpoints(),
+        state,
+    }
+}
@@ -0,0 +1,160 @@
+use crate::business::app::app_dao::AppVersion;
+use crate::business::app_run::run_dao::{AppRun, AppRunNode, RunDao};
+use crate::engine::error::EngineError;
+use crate::engine::runner::RunOutput;
+use crate::engine::trace::NodeTrace;
+use crate::models::app_run::{RunCaller, RunStatus};
+use crate::models::context::Context;
+use crate::utils::id::Id;
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+use uorm::transaction;
+
+// 清理过期运行记录的间隔
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// 一次运行的记录，运行结束后保存运行记录和各节点的执行记录；
+// 保存失败只记录日志，不影响运行结果
+#[derive(Clone)]
+pub struct RunHistory {
+    id: u64,
+    tenant_id: u64,
+    workspace_id: u64,
+    app_id: u64,
+    version_id: u64,
+    version: String,
+    caller: RunCaller,
+    input: Map<String, Value>,
+    started: Instant,
+}
+
+impl RunHistory {
+    pub fn new(
+        ctx: &Context,
+        version: &AppVersion,
+        caller: RunCaller,
+        input: &Map<String, Value>,
+    ) -> Self {
+        Self {
+            id: Id::next_id().unwrap_or_default(),
+            tenant_id: ctx.tenant_id,
+            workspace_id: ctx.workspace_id,
+            app_id: version.app_id,
+            version_id: version.id,
+            version: version.version.clone(),
+            caller,
+            input: input.clone(),
+            started: Instant::now(),
+        }
+    }
+
+    // 运行结束：成功时保存结束节点的输出变量，失败时保存错误码和错误信息
+    pub async fn finish(&self, result: &Result<RunOutput, EngineError>, trace: &[NodeTrace]) {
+        let mut run = self.record(match result {
+            Ok(_) => RunStatus::Succeeded,
+            Err(_) => RunStatus::Failed,
+        });
+        match result {
+            Ok(output) => run.output = to_json(&output.vars),
+            Err(err) => {
+                run.error_code = Some(err.code().into());
+                run.error_message = Some(err.to_string());
+            }
+        }
+
+        let nodes = trace
+            .iter()
+            .enumerate()
+            .map(|(seq, trace)| AppRunNode {
+                run_id: self.id,
+                seq: seq as u64,
+                node_id: trace.node_id.clone(),
+                node_type: trace.node_type.to_string(),
+                input: to_json(&trace.input),
+                output: to_json(&trace.output),
+                elapsed: trace.elapsed,
+                error: trace.error.clone(),
+                logs: if trace.logs.is_empty() {
+                    None
+                } else {
+                    to_json(&trace.logs)
+                },
+            })
+            .collect();
+        self.save(&run, nodes).await;
+    }
+
+    // 运行被中止，节点执行记录随运行任务丢弃
+    pub async fn cancel(&self) {
+        let run = self.record(RunStatus::Cancelled);
+        self.save(&run, Vec::new()).await;
+    }
+
+    async fn save(&self, run: &AppRun, nodes: Vec<AppRunNode>) {
+        if let Err(e) = insert(run, nodes).await {
+            let (id, app_id) = (self.id, self.app_id);
+            tracing::warn!("failed to save run {} of app {}: {}", id, app_id, e);
+        }
+    }
+
+    fn record(&self, status: RunStatus) -> AppRun {
+        AppRun {
+            id: self.id,
+            tenant_id: self.tenant_id,
+            workspace_id: self.workspace_id,
+            app_id: self.app_id,
+            version_id: self.version_id,
+            version: self.version.clone(),
+            status: status.as_str().to_string(),
+            input: to_json(&self.input),
+            output: None,
+            error_code: None,
+            error_message: None,
+            caller_type: self.caller.kind().to_string(),
+            caller_id: self.caller.id(),
+            elapsed: self.started.elapsed().as_millis() as u64,
+            start_time: None,
+            finish_time: None,
+        }
+    }
+}
+
+#[transaction]
+async fn insert(run: &AppRun, nodes: Vec<AppRunNode>) -> uorm::Result<()> {
+    RunDao::insert(run).await?;
+    if !nodes.is_empty() {
+        RunDao::insert_nodes(nodes).await?;
+    }
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+// 定期删除超出保留天数的运行记录，保留天数为 0 时不清理
+pub async fn cleanup(retention_days: u64) {
+    if retention_days == 0 {
+        return;
+    }
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let result = async {
+            let nodes = RunDao::delete_nodes_before(retention_days).await?;
+            let runs = RunDao::delete_before(retention_days).await?;
+            uorm::Result::Ok((runs, nodes))
+        }
+        .await;
+        match result {
+            Ok((0, 0)) => {}
+            Ok((runs, nodes)) => tracing::info!(
+                "deleted {} runs and {} node traces older than {} days",
+                runs,
+                nodes,
+                retention_days
+            ),
+            Err(e) => tracing::warn!("failed to clean up run history: {}", e),
+        }
+    }
+}
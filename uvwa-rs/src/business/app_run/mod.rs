@@ -0,0 +1,6 @@
+pub mod debug;
+pub mod debug_handler;
+pub mod history;
+pub mod publish_handler;
+pub mod run_dao;
+pub mod run_handler;
@@ -0,0 +1,73 @@
+use crate::business::api_key::api_key_dao::{ApiKey, ApiKeyDao};
+use crate::business::app::app_dao::AppDao;
+use crate::business::app_run::history::RunHistory;
+use crate::business::app_run::run_handler::engine;
+use crate::core::code::Code;
+use crate::engine::response::EndResponse;
+use crate::models::app_run::{AppRunReq, RunCaller};
+use crate::models::context::Context;
+use crate::models::spec::AppSpec;
+use crate::utils::api_key;
+use crate::web::error::WebError;
+use crate::web::extract::Json;
+use crate::web::r::R;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::http::header::AUTHORIZATION;
+use axum::response::{IntoResponse, Response};
+
+// 发布的运行接口：外部系统通过 Authorization: Bearer <API 密钥> 调用应用的最新版本，
+// 成功时返回结束节点描述的响应，失败时返回 R 格式的错误
+pub async fn run_published(
+    headers: HeaderMap,
+    Path(id): Path<u64>,
+    Json(req): Json<AppRunReq>,
+) -> Response {
+    match run(&headers, id, req).await {
+        Ok(response) => response.into_response(),
+        Err(err) => R::<()>::err(err).into_response(),
+    }
+}
+
+async fn run(headers: &HeaderMap, id: u64, req: AppRunReq) -> Result<EndResponse, WebError> {
+    let key = authenticate(headers, id).await?;
+    // 以密钥所属的工作空间运行，查找数据源和子流程
+    let ctx = Context {
+        tenant_id: key.tenant_id,
+        user_id: key.create_by,
+        workspace_id: key.workspace_id,
+    };
+
+    let version = AppDao::get_latest_version(ctx.tenant_id, ctx.workspace_id, id).await?;
+    let Some(version) = version else {
+        return Err(WebError::Biz(Code::AppVersionNotExist.into()));
+    };
+    let spec = AppSpec::parse(version.spec.as_deref().unwrap_or_default())
+        .map_err(|_| WebError::Biz(Code::AppSpecInvalid.into()))?;
+
+    let history = RunHistory::new(&ctx, &version, RunCaller::ApiKey(key.id), &req.input);
+    let (result, trace) = engine(&ctx, id, &spec)
+        .conversation(req.conversation)
+        .run_traced(req.input)
+        .await;
+    history.finish(&result, &trace).await;
+    Ok(result?.response)
+}
+
+// 应用级密钥只能调用所属的应用
+async fn authenticate(headers: &HeaderMap, app_id: u64) -> Result<ApiKey, WebError> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or(WebError::Biz(Code::Unauthorized.into()))?;
+
+    let key = ApiKeyDao::get_by_hash(&api_key::hash(token)).await?;
+    match key {
+        Some(key) if key.app_id == 0 || key.app_id == app_id => Ok(key),
+        Some(_) => Err(WebError::Biz(Code::Forbidden.into())),
+        None => Err(WebError::Biz(Code::Unauthorized.into())),
+    }
+}
@@ -0,0 +1,94 @@
+use crate::models::app_run::AppRunQuery;
+use uorm::{Param, sql};
+
+#[derive(Param)]
+pub struct AppRun {
+    pub id: u64,
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    pub app_id: u64,
+    pub version_id: u64,
+    pub version: String,
+    pub status: String,
+    // 调用参数和结束节点输出变量的 JSON
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub error_code: Option<i32>,
+    pub error_message: Option<String>,
+    pub caller_type: String,
+    pub caller_id: u64,
+    // 运行耗时（毫秒），开始时间按结束时间和耗时计算
+    pub elapsed: u64,
+    pub start_time: Option<String>,
+    pub finish_time: Option<String>,
+}
+
+#[derive(Param)]
+pub struct AppRunNode {
+    pub run_id: u64,
+    // 节点开始执行的顺序，从 0 开始
+    pub seq: u64,
+    pub node_id: String,
+    pub node_type: String,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub elapsed: u64,
+    pub error: Option<String>,
+    // 调试日志的 JSON 数组
+    pub logs: Option<String>,
+}
+
+#[sql("app_run")]
+pub struct RunDao;
+
+impl RunDao {
+    #[sql("list")]
+    pub async fn list(
+        tenant_id: u64,
+        workspace_id: u64,
+        query: &AppRunQuery,
+        offset: u64,
+        limit: u64,
+    ) -> uorm::Result<Vec<AppRun>> {
+        exec!()
+    }
+
+    #[sql("count")]
+    pub async fn count(
+        tenant_id: u64,
+        workspace_id: u64,
+        query: &AppRunQuery,
+    ) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("get")]
+    pub async fn get(tenant_id: u64, workspace_id: u64, id: u64) -> uorm::Result<Option<AppRun>> {
+        exec!()
+    }
+
+    #[sql("insert")]
+    pub async fn insert(run: &AppRun) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("listNodes")]
+    pub async fn list_nodes(run_id: u64) -> uorm::Result<Vec<AppRunNode>> {
+        exec!()
+    }
+
+    #[sql("insertNodes")]
+    pub async fn insert_nodes(nodes: Vec<AppRunNode>) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("deleteBefore")]
+    pub async fn delete_before(days: u64) -> uorm::Result<u64> {
+        exec!()
+    }
+
+    #[sql("deleteNodesBefore")]
+    pub async fn delete_nodes_before(days: u64) -> uorm::Result<u64> {
+        exec!()
+    }
+}
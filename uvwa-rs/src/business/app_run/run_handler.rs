@@ -0,0 +1,233 @@
+use crate::business::app::app_dao::{AppDao, AppVersion};
+use crate::business::app::subflow::WorkspaceFlowLoader;
+use crate::business::app_run::history::RunHistory;
+use crate::business::app_run::run_dao::RunDao;
+use crate::business::datasource::sql_executor::WorkspaceSqlExecutor;
+use crate::core::code::Code;
+use crate::engine::error::EngineError;
+use crate::engine::event::RunEvent;
+use crate::engine::runner::{Engine, EngineOptions, RunOutput};
+use crate::engine::trace::NodeTrace;
+use crate::models::app_run::{
+    AppRunDetailResp, AppRunQuery, AppRunRecordResp, AppRunReq, AppRunResp, NodeTestReq, RunCaller,
+};
+use crate::models::context::Context;
+use crate::models::page::{PageResp, page_bounds};
+use crate::models::spec::AppSpec;
+use crate::r;
+use crate::web::error::WebError;
+use crate::web::extract::Json;
+use crate::web::r::R;
+use crate::web::server::shutdown_requested;
+use axum::extract::{Path, Query};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::Stream;
+use futures_util::stream;
+use serde_json::{Value, json};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
+
+// 运行已发布的应用版本
+pub async fn run_app_version(
+    ctx: Context,
+    Path((id, version_id)): Path<(u64, u64)>,
+    Json(req): Json<AppRunReq>,
+) -> R<AppRunResp> {
+    let (version, spec) = r!(load_version(&ctx, id, version_id).await);
+    let history = RunHistory::new(&ctx, &version, RunCaller::User(ctx.user_id), &req.input);
+    let (result, trace) = engine(&ctx, id, &spec)
+        .conversation(req.conversation)
+        .run_traced(req.input)
+        .await;
+    history.finish(&result, &trace).await;
+
+    let output = r!(result);
+    R::ok(RunOutput { trace, ..output }.into())
+}
+
+// 流式运行已发布的应用版本，通过 SSE 推送：
+// node_started/node_finished 节点进度，text 结束节点的文本片段（需开启流式输出），
+// 最后为 done（与非流式接口的 data 相同）或 error（与 R 的错误格式相同）。
+// 客户端断开或服务关闭时中止运行
+pub async fn stream_app_version(
+    ctx: Context,
+    Path((id, version_id)): Path<(u64, u64)>,
+    Json(req): Json<AppRunReq>,
+) -> Response {
+    let (version, spec) = match load_version(&ctx, id, version_id).await {
+        Ok(loaded) => loaded,
+        Err(err) => return R::<()>::err(err).into_response(),
+    };
+    let history = RunHistory::new(&ctx, &version, RunCaller::User(ctx.user_id), &req.input);
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let recorder = history.clone();
+    let finishing = Arc::new(AtomicBool::new(false));
+    let flag = finishing.clone();
+    let task = tokio::spawn(async move {
+        let (result, trace) = engine(&ctx, id, &spec)
+            .events(sender)
+            .conversation(req.conversation)
+            .run_traced(req.input)
+            .await;
+        // 运行已结束，之后断开也不再中止，保证运行记录完整写入
+        flag.store(true, Ordering::Release);
+        recorder.finish(&result, &trace).await;
+        result.map(|output| RunOutput { trace, ..output })
+    });
+    let task = RunTask {
+        task,
+        history,
+        finishing,
+    };
+    Sse::new(run_events(receiver, task))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+// 单独测试代码或 SQL 节点，与流程运行使用相同的执行器；
+// 节点执行失败不视为请求失败，错误信息在返回的执行记录中
+pub async fn test_node(
+    ctx: Context,
+    Path((id, node_id)): Path<(u64, String)>,
+    Json(req): Json<NodeTestReq>,
+) -> R<NodeTrace> {
+    let spec = match req.node {
+        Some(mut node) => {
+            let exist = r!(AppDao::get_by_id(ctx.tenant_id, ctx.workspace_id, id).await);
+            if exist.is_none() {
+                return R::err(WebError::Biz(Code::AppNotExist.into()));
+            }
+            // 节点 id 以路径为准，不是对象时按草稿不合法处理
+            if let Some(node) = node.as_object_mut() {
+                node.insert("id".to_string(), Value::String(node_id.clone()));
+            }
+            json!({ "nodes": [node] }).to_string()
+        }
+        None => {
+            let spec = r!(AppDao::get_spec(ctx.tenant_id, ctx.workspace_id, id).await);
+            let Some(spec) = spec else {
+                return R::err(WebError::Biz(Code::AppDraftNotExist.into()));
+            };
+            spec
+        }
+    };
+    let Ok(spec) = AppSpec::parse(&spec) else {
+        return R::err(WebError::Biz(Code::AppSpecInvalid.into()));
+    };
+    let Some(node) = spec.node(&node_id) else {
+        return R::err(WebError::Biz(Code::AppNodeNotExist.into()));
+    };
+
+    R::from(engine(&ctx, id, &spec).run_node(node, &req.input).await)
+}
+
+// 分页查询运行记录
+pub async fn list_runs(
+    ctx: Context,
+    Query(req): Query<AppRunQuery>,
+) -> R<PageResp<AppRunRecordResp>> {
+    let (offset, limit) = page_bounds(req.page_no, req.page_size);
+    let total = r!(RunDao::count(ctx.tenant_id, ctx.workspace_id, &req).await);
+    let runs = r!(RunDao::list(ctx.tenant_id, ctx.workspace_id, &req, offset, limit).await);
+
+    let items = runs.iter().map(Into::into).collect();
+    R::ok(PageResp::new(total, items))
+}
+
+// 运行详情，包含各节点的执行记录
+pub async fn get_run(ctx: Context, Path(id): Path<u64>) -> R<AppRunDetailResp> {
+    let Some(run) = r!(RunDao::get(ctx.tenant_id, ctx.workspace_id, id).await) else {
+        return R::err(WebError::Biz(Code::AppRunNotExist.into()));
+    };
+    let nodes = r!(RunDao::list_nodes(run.id).await);
+    R::ok((run, nodes).into())
+}
+
+async fn load_version(
+    ctx: &Context,
+    id: u64,
+    version_id: u64,
+) -> Result<(AppVersion, AppSpec), WebError> {
+    let version = AppDao::get_version(ctx.tenant_id, ctx.workspace_id, id, version_id).await?;
+    let Some(version) = version else {
+        return Err(WebError::Biz(Code::AppVersionNotExist.into()));
+    };
+
+    let spec = AppSpec::parse(version.spec.as_deref().unwrap_or_default())
+        .map_err(|_| WebError::Biz(Code::AppSpecInvalid.into()))?;
+    Ok((version, spec))
+}
+
+// 按当前工作空间查找数据源和子流程
+pub fn engine<'a>(ctx: &Context, id: u64, spec: &'a AppSpec) -> Engine<'a> {
+    let sql = WorkspaceSqlExecutor {
+        tenant_id: ctx.tenant_id,
+        workspace_id: ctx.workspace_id,
+        max_rows: EngineOptions::global().sql_max_rows,
+    };
+    let flows = WorkspaceFlowLoader {
+        tenant_id: ctx.tenant_id,
+        workspace_id: ctx.workspace_id,
+    };
+    Engine::new(spec)
+        .sql(Arc::new(sql))
+        .flows(Arc::new(flows))
+        .flow_id(id.to_string())
+}
+
+// 运行中的任务，响应流被丢弃（客户端断开）时中止，
+// 进行中的事务随连接回滚，Python 子进程随之结束；未结束的运行记录为已中止
+struct RunTask {
+    task: JoinHandle<Result<RunOutput, EngineError>>,
+    history: RunHistory,
+    // 正在写入运行记录
+    finishing: Arc<AtomicBool>,
+}
+
+impl Drop for RunTask {
+    fn drop(&mut self) {
+        if self.task.is_finished() || self.finishing.load(Ordering::Acquire) {
+            return;
+        }
+        self.task.abort();
+        let history = self.history.clone();
+        tokio::spawn(async move { history.cancel().await });
+    }
+}
+
+// 先转发运行事件，发送方关闭（运行结束）后输出最终结果
+fn run_events(
+    receiver: UnboundedReceiver<RunEvent>,
+    task: RunTask,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(Some((receiver, task)), |state| async move {
+        let (mut receiver, mut task) = state?;
+        let event = tokio::select! {
+            event = receiver.recv() => event,
+            // 服务关闭时结束流，否则优雅关闭会一直等待该连接
+            _ = shutdown_requested() => return None,
+        };
+        if let Some(event) = event {
+            let sse = Event::default().event(event.name()).json_data(&event);
+            return Some((Ok(sse.unwrap_or_default()), Some((receiver, task))));
+        }
+
+        let sse = match (&mut task.task).await {
+            Ok(Ok(output)) => Event::default()
+                .event("done")
+                .json_data(AppRunResp::from(output)),
+            Ok(Err(err)) => Event::default()
+                .event("error")
+                .json_data(R::<()>::err(err.into())),
+            Err(err) => Event::default()
+                .event("error")
+                .json_data(R::<()>::err(WebError::Sys(err.to_string()))),
+        };
+        Some((Ok(sse.unwrap_or_default()), None))
+    })
+}
@@ -0,0 +1,45 @@
+use crate::business::datasource::connector::{CONNECT_TIMEOUT, ConnectOptions};
+use crate::business::datasource::datasource_dao::Connection;
+use crate::web::error::WebError;
+use moka::future::Cache;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use uorm::udbc::PoolOptions;
+use uorm::udbc::driver::Driver;
+use uorm::udbc::mysql::pool::MysqlDriver;
+
+// 数据源连接池缓存： connection_id -> 连接池，闲置一段时间后关闭
+pub static DATASOURCE_POOL: LazyLock<Cache<u64, Arc<MysqlDriver>>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(1_000)
+        .time_to_idle(Duration::from_secs(30 * 60))
+        .eviction_listener(|_, driver: Arc<MysqlDriver>, _| {
+            tokio::spawn(async move {
+                let _ = driver.close().await;
+            });
+        })
+        .build()
+});
+
+pub async fn get_pool(conn: &Connection) -> Result<Arc<MysqlDriver>, Arc<WebError>> {
+    DATASOURCE_POOL
+        .try_get_with(conn.id, async {
+            let options = ConnectOptions::from_connection(conn)?;
+            let driver = options.driver(
+                &format!("datasource-{}", conn.id),
+                PoolOptions {
+                    max_open_conns: 10,
+                    max_idle_conns: 2,
+                    max_lifetime: 30 * 60,
+                    timeout: CONNECT_TIMEOUT.as_secs(),
+                },
+            )?;
+            Ok(Arc::new(driver))
+        })
+        .await
+}
+
+// 连接信息修改或删除后移除旧的连接池
+pub async fn evict_pool(id: u64) {
+    DATASOURCE_POOL.invalidate(&id).await;
+}
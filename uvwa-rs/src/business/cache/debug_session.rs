@@ -0,0 +1,40 @@
+use crate::business::app_run::debug::DebugSession;
+use moka::future::Cache;
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+// 每个用户同时保留的调试会话数
+const MAX_SESSIONS_PER_USER: usize = 3;
+
+// 调试会话缓存：session_id -> 会话，过期或删除后会话被丢弃，运行随之中止；
+// 暂停的会话可能持有事务和连接，5 分钟没有访问即丢弃，不必等到 30 分钟的存活时间
+pub static DEBUG_SESSION_CACHE: LazyLock<Cache<u64, Arc<DebugSession>>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(1_000)
+        .time_to_live(Duration::from_secs(30 * 60))
+        .time_to_idle(Duration::from_secs(5 * 60))
+        .build()
+});
+
+pub async fn get_session(session_id: u64) -> Option<Arc<DebugSession>> {
+    DEBUG_SESSION_CACHE.get(&session_id).await
+}
+
+// 同一用户的会话超出上限时丢弃最早创建的会话（会话 ID 递增）
+pub async fn save_session(session: Arc<DebugSession>) {
+    let mut owned: Vec<u64> = DEBUG_SESSION_CACHE
+        .iter()
+        .filter(|(_, s)| s.tenant_id == session.tenant_id && s.user_id == session.user_id)
+        .map(|(id, _)| *id)
+        .collect();
+    owned.sort_unstable();
+    let evicted = (owned.len() + 1).saturating_sub(MAX_SESSIONS_PER_USER);
+    for id in &owned[..evicted] {
+        DEBUG_SESSION_CACHE.invalidate(id).await;
+    }
+    DEBUG_SESSION_CACHE.insert(session.id, session).await;
+}
+
+pub async fn remove_session(session_id: u64) {
+    DEBUG_SESSION_CACHE.invalidate(&session_id).await;
+}
@@ -1 +1,3 @@
 pub mod workspace_cache;
+pub mod datasource_pool;
+pub mod debug_session;
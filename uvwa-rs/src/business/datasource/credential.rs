@@ -0,0 +1,26 @@
+use crate::business::datasource::datasource_dao::{Connection, DatasourceDao};
+use crate::utils::secret::Secret;
+
+// 返回给前端的密码掩码，更新时提交掩码表示不修改密码
+pub const PASSWORD_MASK: &str = "******";
+
+pub fn encrypt_password(password: &str) -> anyhow::Result<String> {
+    Secret::global()?.encrypt(password)
+}
+
+pub fn decrypt_password(conn: &Connection) -> anyhow::Result<String> {
+    Secret::global()?.decrypt(&conn.password)
+}
+
+// 密钥轮换：用主密钥重新加密所有连接的密码（包括历史明文），返回更新的数量
+pub async fn reencrypt_passwords() -> anyhow::Result<usize> {
+    let secret = Secret::global()?;
+    let mut count = 0;
+    for conn in DatasourceDao::list_all().await? {
+        if let Some(password) = secret.reencrypt(&conn.password)? {
+            DatasourceDao::update_password(conn.id, &password).await?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
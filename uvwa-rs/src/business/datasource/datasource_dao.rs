@@ -0,0 +1,73 @@
+use crate::models::datasource::ConnectionReq;
+use uorm::{Param, sql};
+
+#[derive(Param)]
+pub struct Connection {
+    pub id: u64,
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    pub conn_key: String,
+    pub name: String,
+    pub conn_type: Option<String>,
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    pub create_time: Option<String>,
+}
+
+#[sql("datasource")]
+pub struct DatasourceDao;
+
+impl DatasourceDao {
+    #[sql("list")]
+    pub async fn list(
+        tenant_id: u64,
+        workspace_id: u64,
+        query: &ConnectionReq,
+    ) -> uorm::Result<Vec<Connection>> {
+        exec!()
+    }
+
+    #[sql("get")]
+    pub async fn get(
+        tenant_id: u64,
+        workspace_id: u64,
+        id: u64,
+    ) -> uorm::Result<Option<Connection>> {
+        exec!()
+    }
+
+    #[sql("getByKey")]
+    pub async fn get_by_key(
+        tenant_id: u64,
+        workspace_id: u64,
+        conn_key: &str,
+    ) -> uorm::Result<Option<Connection>> {
+        exec!()
+    }
+
+    #[sql("insert")]
+    pub async fn insert(conn: &Connection) -> uorm::Result<()> {
+        exec!()
+    }
+
+    #[sql("update")]
+    pub async fn update(conn: &Connection) -> uorm::Result<()> {
+        exec!()
+    }
+
+    #[sql("listAll")]
+    pub async fn list_all() -> uorm::Result<Vec<Connection>> {
+        exec!()
+    }
+
+    #[sql("updatePassword")]
+    pub async fn update_password(id: u64, password: &str) -> uorm::Result<()> {
+        exec!()
+    }
+
+    #[sql("delete")]
+    pub async fn delete(tenant_id: u64, workspace_id: u64, id: u64) -> uorm::Result<()> {
+        exec!()
+    }
+}
@@ -0,0 +1,128 @@
+use crate::business::cache::datasource_pool::evict_pool;
+use crate::business::datasource::connector::{self, ConnectOptions};
+use crate::business::datasource::credential::{PASSWORD_MASK, encrypt_password};
+use crate::business::datasource::datasource_dao::{Connection, DatasourceDao};
+use crate::core::code::Code;
+use crate::models::context::Context;
+use crate::models::datasource::{
+    ConnectionCreateReq, ConnectionReq, ConnectionResp, ConnectionTestReq, ConnectionTestResp,
+    ConnectionUpdateReq,
+};
+use crate::r;
+use crate::web::error::WebError;
+use crate::web::extract::Json;
+use crate::web::r::R;
+use axum::extract::{Path, Query};
+use validator::Validate;
+
+// 查询数据库连接列表，由前端分页
+pub async fn list_connections(
+    ctx: Context,
+    Query(req): Query<ConnectionReq>,
+) -> R<Vec<ConnectionResp>> {
+    let conns = r!(DatasourceDao::list(ctx.tenant_id, ctx.workspace_id, &req).await);
+    R::ok(conns.into_iter().map(Into::into).collect())
+}
+
+// 获取数据库连接
+pub async fn get_connection(ctx: Context, Path(id): Path<u64>) -> R<ConnectionResp> {
+    match r!(DatasourceDao::get(ctx.tenant_id, ctx.workspace_id, id).await) {
+        Some(conn) => R::ok(conn.into()),
+        None => R::err(WebError::Biz(Code::DatasourceNotExist.into())),
+    }
+}
+
+// 创建数据库连接
+pub async fn create_connection(
+    ctx: Context,
+    Json(req): Json<ConnectionCreateReq>,
+) -> R<ConnectionResp> {
+    r!(req.validate());
+
+    // 连接标识符在工作空间内唯一，SQL 节点通过它引用连接
+    let exist = r!(DatasourceDao::get_by_key(ctx.tenant_id, ctx.workspace_id, &req.conn_key).await);
+    if exist.is_some() {
+        return R::err(key_duplicate(&req.conn_key));
+    }
+
+    let mut conn: Connection = (ctx.tenant_id, ctx.workspace_id, req).into();
+    conn.password = r!(encrypt_password(&conn.password));
+    r!(DatasourceDao::insert(&conn).await);
+    R::ok(r!(saved_connection(&ctx, conn.id).await))
+}
+
+// 更新数据库连接
+pub async fn update_connection(
+    ctx: Context,
+    Path(id): Path<u64>,
+    Json(req): Json<ConnectionUpdateReq>,
+) -> R<ConnectionResp> {
+    r!(req.validate());
+
+    let mut conn = match r!(DatasourceDao::get(ctx.tenant_id, ctx.workspace_id, id).await) {
+        Some(conn) => conn,
+        None => return R::err(WebError::Biz(Code::DatasourceNotExist.into())),
+    };
+
+    if conn.conn_key != req.conn_key {
+        let exist =
+            r!(DatasourceDao::get_by_key(ctx.tenant_id, ctx.workspace_id, &req.conn_key).await);
+        if exist.is_some_and(|c| c.id != id) {
+            return R::err(key_duplicate(&req.conn_key));
+        }
+    }
+
+    conn.conn_key = req.conn_key;
+    conn.name = req.name;
+    conn.conn_type = req.conn_type;
+    conn.url = req.url;
+    conn.username = req.username;
+    // 未填写或提交掩码时保留原密码
+    if let Some(password) = req.password.filter(|p| !p.is_empty() && p != PASSWORD_MASK) {
+        conn.password = r!(encrypt_password(&password));
+    }
+    r!(DatasourceDao::update(&conn).await);
+    evict_pool(id).await;
+    R::ok(r!(saved_connection(&ctx, id).await))
+}
+
+// 删除数据库连接
+pub async fn delete_connection(ctx: Context, Path(id): Path<u64>) -> R<()> {
+    r!(DatasourceDao::delete(ctx.tenant_id, ctx.workspace_id, id).await);
+    evict_pool(id).await;
+    R::void()
+}
+
+// 测试填写的连接信息，用于保存前检查
+pub async fn test_connection(Json(req): Json<ConnectionTestReq>) -> R<ConnectionTestResp> {
+    r!(req.validate());
+    let mut options = req.into();
+    r!(connector::pin_public_host(&mut options).await);
+    R::from(connector::ping(&options).await)
+}
+
+// 测试已保存的数据库连接
+pub async fn test_saved_connection(ctx: Context, Path(id): Path<u64>) -> R<ConnectionTestResp> {
+    let conn = match r!(DatasourceDao::get(ctx.tenant_id, ctx.workspace_id, id).await) {
+        Some(conn) => conn,
+        None => return R::err(WebError::Biz(Code::DatasourceNotExist.into())),
+    };
+
+    let options = r!(ConnectOptions::from_connection(&conn));
+    R::from(connector::ping(&options).await)
+}
+
+// 保存后重新读取，返回与列表相同的内容（包括创建时间）
+async fn saved_connection(ctx: &Context, id: u64) -> Result<ConnectionResp, WebError> {
+    match DatasourceDao::get(ctx.tenant_id, ctx.workspace_id, id).await? {
+        Some(conn) => Ok(conn.into()),
+        None => Err(WebError::Biz(Code::DatasourceNotExist.into())),
+    }
+}
+
+fn key_duplicate(key: &str) -> WebError {
+    WebError::BizWithArgs(
+        Code::DatasourceKeyDuplicate.into(),
+        vec![("key".to_string(), key.to_string())],
+    )
+}
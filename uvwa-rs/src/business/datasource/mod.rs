@@ -0,0 +1,5 @@
+pub mod connector;
+pub mod credential;
+pub mod datasource_dao;
+pub mod datasource_handler;
+pub mod sql_executor;
@@ -0,0 +1,373 @@
+use crate::business::cache::datasource_pool::get_pool;
+use crate::business::datasource::datasource_dao::DatasourceDao;
+use crate::engine::sql::{SqlExecutor, SqlResult, SqlStatement, SqlTransaction, first_keyword};
+use crate::web::error::WebError;
+use crate::web::r::translate;
+use async_trait::async_trait;
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+use tokio::runtime::Handle;
+use uorm::udbc::connection::Connection as DbConnection;
+use uorm::udbc::driver::Driver;
+
+// 在工作空间的数据源上执行 SQL 节点，按连接标识符查找数据源
+pub struct WorkspaceSqlExecutor {
+    pub tenant_id: u64,
+    pub workspace_id: u64,
+    // 查询结果的最大行数
+    pub max_rows: usize,
+}
+
+impl WorkspaceSqlExecutor {
+    async fn acquire(&self, conn_key: &str) -> Result<Box<dyn DbConnection>, String> {
+        let conn = DatasourceDao::get_by_key(self.tenant_id, self.workspace_id, conn_key)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("datasource {} is not found", conn_key))?;
+        let pool = get_pool(&conn).await.map_err(|e| message(&e))?;
+        pool.acquire().await.map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl SqlExecutor for WorkspaceSqlExecutor {
+    async fn execute(&self, conn_key: &str, statement: &SqlStatement) -> Result<SqlResult, String> {
+        let mut db = self.acquire(conn_key).await?;
+        run(db.as_mut(), statement, self.max_rows).await
+    }
+
+    async fn begin(&self, conn_key: &str) -> Result<Box<dyn SqlTransaction>, String> {
+        let db = self.acquire(conn_key).await?;
+        Ok(Box::new(ConnTransaction::begin(db, self.max_rows).await?))
+    }
+}
+
+const FINISHED: &str = "transaction is already finished";
+
+// 独占一个连接的事务，连接在提交或回滚后随之释放
+pub struct ConnTransaction {
+    // 提交或回滚时取出，未取出说明事务没有结束
+    db: Option<Box<dyn DbConnection>>,
+    max_rows: usize,
+}
+
+impl ConnTransaction {
+    pub async fn begin(mut db: Box<dyn DbConnection>, max_rows: usize) -> Result<Self, String> {
+        db.begin().await.map_err(|e| e.to_string())?;
+        Ok(Self {
+            db: Some(db),
+            max_rows,
+        })
+    }
+
+    fn take(&mut self) -> Result<Box<dyn DbConnection>, String> {
+        self.db.take().ok_or_else(|| FINISHED.to_string())
+    }
+}
+
+#[async_trait]
+impl SqlTransaction for ConnTransaction {
+    async fn execute(&mut self, statement: &SqlStatement) -> Result<SqlResult, String> {
+        let db = self.db.as_mut().ok_or_else(|| FINISHED.to_string())?;
+        run(db.as_mut(), statement, self.max_rows).await
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<(), String> {
+        self.take()?.commit().await.map_err(|e| e.to_string())
+    }
+
+    async fn rollback(mut self: Box<Self>) -> Result<(), String> {
+        self.take()?.rollback().await.map_err(|e| e.to_string())
+    }
+}
+
+// 运行在事务中途被中止时回滚，避免未结束的事务随连接回到连接池；
+// 没有运行时无法回滚，直接丢弃连接
+impl Drop for ConnTransaction {
+    fn drop(&mut self) {
+        if let Some(mut db) = self.db.take()
+            && let Ok(handle) = Handle::try_current()
+        {
+            handle.spawn(async move {
+                let _ = db.rollback().await;
+            });
+        }
+    }
+}
+
+// 查询语句返回结果集，其他语句返回影响的行数；结果集超过 max_rows 行时报错，
+// 需要在 SQL 中加 LIMIT 或缩小查询条件
+async fn run(
+    db: &mut dyn DbConnection,
+    statement: &SqlStatement,
+    max_rows: usize,
+) -> Result<SqlResult, String> {
+    // MySQL 按位置绑定参数，参数名不参与绑定
+    let params: Vec<(String, uorm::Value)> = statement
+        .params
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (i.to_string(), to_db(v)))
+        .collect();
+
+    if statement.query {
+        let rows = db
+            .query(&limit_rows(&statement.sql, max_rows), &params)
+            .await
+            .map_err(|e| e.to_string())?;
+        if rows.len() > max_rows {
+            return Err(format!(
+                "query returned {} rows, more than the limit of {}",
+                rows.len(),
+                max_rows
+            ));
+        }
+        let affected = rows.len() as u64;
+        let rows = rows.into_iter().map(to_row).collect();
+        Ok(SqlResult { rows, affected })
+    } else {
+        let affected = db
+            .execute(&statement.sql, &params)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(SqlResult {
+            rows: vec![],
+            affected,
+        })
+    }
+}
+
+// 在数据库中限制行数，最多多取一行用于判断是否超限，避免超大结果集读入内存。
+// 结果行按列名转为对象，同名列本来就会相互覆盖，包成子查询后要求列名唯一不影响结果；
+// SHOW、DESC、EXPLAIN 不能作为子查询，结果也很小，原样执行
+fn limit_rows(sql: &str, max_rows: usize) -> String {
+    match first_keyword(sql).as_str() {
+        "select" | "with" | "values" => {
+            let sql = sql.trim_end().trim_end_matches(';');
+            // 换行结束子查询，避免被末尾的单行注释吞掉
+            format!(
+                "SELECT * FROM (\n{}\n) t LIMIT {}",
+                sql,
+                max_rows.saturating_add(1)
+            )
+        }
+        _ => sql.to_string(),
+    }
+}
+
+fn message(e: &WebError) -> String {
+    match e {
+        WebError::Biz(code) => translate(*code, &vec![]),
+        WebError::BizWithArgs(code, args) => translate(*code, args),
+        e => e.to_string(),
+    }
+}
+
+// 对象和数组参数按 JSON 文本绑定
+fn to_db(value: &Value) -> uorm::Value {
+    match value {
+        Value::Null => uorm::Value::Null,
+        Value::Bool(b) => uorm::Value::Bool(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => uorm::Value::I64(i),
+            (_, Some(u)) => uorm::Value::U64(u),
+            _ => uorm::Value::F64(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => uorm::Value::Str(s.clone()),
+        value => uorm::Value::Str(value.to_string()),
+    }
+}
+
+fn to_row(row: HashMap<String, uorm::Value>) -> Map<String, Value> {
+    row.into_iter().map(|(k, v)| (k, to_json(v))).collect()
+}
+
+// 文本列以字节返回，按 UTF-8 转为字符串；日期时间转为字符串
+fn to_json(value: uorm::Value) -> Value {
+    use uorm::Value as V;
+    match value {
+        V::Null => Value::Null,
+        V::Bool(b) => Value::Bool(b),
+        V::Char(c) => Value::String(c.to_string()),
+        V::Str(s) => Value::String(s),
+        V::I8(i) => Value::from(i),
+        V::I16(i) => Value::from(i),
+        V::I32(i) => Value::from(i),
+        V::I64(i) => Value::from(i),
+        V::I128(i) => i64::try_from(i).map_or(Value::String(i.to_string()), Value::from),
+        V::U8(u) => Value::from(u),
+        V::U16(u) => Value::from(u),
+        V::U32(u) => Value::from(u),
+        V::U64(u) => Value::from(u),
+        V::U128(u) => u64::try_from(u).map_or(Value::String(u.to_string()), Value::from),
+        V::F32(f) => Number::from_f64(f as f64).map_or(Value::Null, Value::Number),
+        V::F64(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        V::Bytes(b) => Value::String(String::from_utf8_lossy(&b).to_string()),
+        V::Date(d) => Value::String(d.to_string()),
+        V::Time(t) => Value::String(t.to_string()),
+        V::DateTime(dt) => Value::String(dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+        V::DateTimeUtc(dt) => Value::String(dt.to_rfc3339()),
+        // 超出精度的小数保留为字符串
+        V::Decimal(d) => {
+            let text = d.to_string();
+            match text.parse::<f64>().ok().and_then(Number::from_f64) {
+                Some(n) if n.to_string() == text => Value::Number(n),
+                _ => Value::String(text),
+            }
+        }
+        V::List(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        V::Map(map) => Value::Object(to_row(map)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::runner::Engine;
+    use crate::models::spec::AppSpec;
+    use serde_json::json;
+    use std::sync::Arc;
+    use uorm::udbc::sqlite::pool::SqliteDriver;
+
+    // 本地 SQLite 数据库，与 MySQL 共用连接和事务的实现
+    struct SqliteExecutor(SqliteDriver);
+
+    const MAX_ROWS: usize = 100;
+
+    #[async_trait]
+    impl SqlExecutor for SqliteExecutor {
+        async fn execute(
+            &self,
+            _conn_key: &str,
+            statement: &SqlStatement,
+        ) -> Result<SqlResult, String> {
+            let mut db = self.0.acquire().await.map_err(|e| e.to_string())?;
+            run(db.as_mut(), statement, MAX_ROWS).await
+        }
+
+        async fn begin(&self, _conn_key: &str) -> Result<Box<dyn SqlTransaction>, String> {
+            let db = self.0.acquire().await.map_err(|e| e.to_string())?;
+            Ok(Box::new(ConnTransaction::begin(db, MAX_ROWS).await?))
+        }
+    }
+
+    #[test]
+    fn test_to_json() {
+        let row = HashMap::from([
+            ("name".to_string(), uorm::Value::Bytes(b"uvwa".to_vec())),
+            ("age".to_string(), uorm::Value::I32(18)),
+            ("deleted".to_string(), uorm::Value::Null),
+        ]);
+        assert_eq!(
+            Value::Object(to_row(row)),
+            json!({"name": "uvwa", "age": 18, "deleted": null})
+        );
+        assert_eq!(to_json(uorm::Value::F64(f64::NAN)), Value::Null);
+        assert!(matches!(to_db(&json!(1)), uorm::Value::I64(1)));
+        assert!(matches!(to_db(&json!([1, 2])), uorm::Value::Str(s) if s == "[1,2]"));
+    }
+
+    #[tokio::test]
+    async fn test_max_rows() {
+        let path = std::env::temp_dir().join(format!("uvwa-rows-{}.db", std::process::id()));
+        let driver = SqliteDriver::new(path.to_string_lossy()).build().unwrap();
+        let mut db = driver.acquire().await.unwrap();
+        let statement = SqlStatement {
+            sql: "SELECT 1 AS n UNION ALL SELECT 2 UNION ALL SELECT 3".to_string(),
+            params: vec![],
+            query: true,
+        };
+
+        let result = run(db.as_mut(), &statement, 3).await.unwrap();
+        assert_eq!(result.affected, 3);
+        let err = run(db.as_mut(), &statement, 2).await.unwrap_err();
+        assert!(err.contains("limit of 2"), "{}", err);
+
+        // 末尾的分号和单行注释不影响子查询
+        for sql in ["SELECT 1 AS n;", "SELECT 1 AS n -- one row"] {
+            let statement = SqlStatement {
+                sql: sql.to_string(),
+                params: vec![],
+                query: true,
+            };
+            assert_eq!(run(db.as_mut(), &statement, 1).await.unwrap().affected, 1);
+        }
+        assert_eq!(limit_rows("show tables", 10), "show tables");
+        assert_eq!(
+            limit_rows("select * from user;", 10),
+            "SELECT * FROM (\nselect * from user\n) t LIMIT 11"
+        );
+
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rollback() {
+        let path = std::env::temp_dir().join(format!("uvwa-tx-{}.db", std::process::id()));
+        let driver = SqliteDriver::new(path.to_string_lossy()).build().unwrap();
+        let mut db = driver.acquire().await.unwrap();
+        db.execute(
+            "CREATE TABLE user (id INTEGER PRIMARY KEY, name TEXT NOT NULL)",
+            &[],
+        )
+        .await
+        .unwrap();
+
+        // 事务容器内先插入数据，再由代码节点校验，校验失败时整个事务回滚
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [{"id": "name", "name": "name", "type": "STRING"}]}},
+                {"id": "t", "type": "sql-transaction", "data": {"connKey": "main"}},
+                {"id": "i", "type": "sql", "parentId": "t", "data": {
+                    "connKey": "main",
+                    "content": "INSERT INTO user (name) VALUES (#{name})",
+                    "input": [{"name": "name", "value": "name"}],
+                    "output": [{"id": "rowNum", "name": "rowNum", "type": "INTEGER"}]
+                }},
+                {"id": "u", "type": "sql", "parentId": "t", "data": {
+                    "content": "UPDATE user SET name = name || '!' WHERE name = #{name}",
+                    "input": [{"name": "name", "value": "name"}],
+                    "output": [{"id": "updated", "name": "rowNum", "type": "INTEGER"}]
+                }},
+                {"id": "c", "type": "code", "parentId": "t", "data": {
+                    "language": "javascript", "timeout": "1",
+                    "content": "function main({name}) { if (name === 'bad') throw new Error('invalid name'); return {}; }",
+                    "input": [{"name": "name", "value": "name"}]
+                }},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [{"name": "updated", "value": "updated"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "t"},
+                {"id": "2", "source": "i", "target": "u"},
+                {"id": "3", "source": "u", "target": "c"},
+                {"id": "4", "source": "t", "target": "e"}
+            ]
+        }"#;
+        let spec = AppSpec::parse(spec).unwrap();
+        let executor = Arc::new(SqliteExecutor(driver));
+        let run = |name: &str| {
+            let Value::Object(input) = json!({"name": name}) else {
+                unreachable!()
+            };
+            let engine = Engine::new(&spec).sql(executor.clone());
+            async move { engine.run(input).await }
+        };
+
+        run("good").await.unwrap();
+        assert!(run("bad").await.is_err());
+
+        let names = db
+            .query("SELECT name FROM user ORDER BY id", &[])
+            .await
+            .unwrap();
+        let names: Vec<Value> = names
+            .into_iter()
+            .map(|r| Value::Object(to_row(r)))
+            .collect();
+        assert_eq!(names, vec![json!({"name": "good!"})]);
+
+        drop(db);
+        let _ = std::fs::remove_file(&path);
+    }
+}
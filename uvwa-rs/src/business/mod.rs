@@ -1,5 +1,8 @@
+pub mod api_key;
 pub mod app;
+pub mod app_run;
 pub mod cache;
+pub mod datasource;
 pub mod user;
 pub mod workspace;
 pub mod workspace_folder;
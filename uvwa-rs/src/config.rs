@@ -24,11 +24,52 @@ pub struct LoggerConfig {
     pub console: bool,
 }
 
+#[derive(Deserialize)]
+pub struct EngineConfig {
+    /// 单个循环节点的最大迭代次数
+    pub max_loop_iterations: u64,
+    /// 代码节点未配置超时时间时的默认超时（秒）
+    pub script_timeout: u64,
+    /// JavaScript 代码节点的内存上限（MB）
+    pub script_memory_mb: u64,
+    /// 是否允许执行 Python 代码节点
+    pub python_enabled: bool,
+    /// Python 子进程是否在系统级沙箱中运行
+    pub python_sandbox: bool,
+    /// python 可执行文件路径
+    pub python: String,
+    /// 同时运行的 Python 子进程数量
+    pub python_workers: usize,
+    /// Python 子进程的内存上限（MB）
+    pub python_memory_mb: u64,
+    /// 子流程的最大嵌套层数
+    pub max_subflow_depth: u64,
+    /// SQL 节点查询结果的最大行数
+    pub sql_max_rows: usize,
+}
+
+#[derive(Deserialize)]
+pub struct HistoryConfig {
+    /// 运行记录的保留天数，0 表示不清理
+    pub retention_days: u64,
+}
+
+#[derive(Deserialize)]
+pub struct SecretConfig {
+    /// 凭证加密主密钥，base64 编码的 32 字节
+    pub master_key: String,
+    /// 轮换前使用的旧密钥，逗号分隔，只用于解密
+    pub previous_keys: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct AppConfig {
     pub server: String,
     pub logger: LoggerConfig,
     pub database: DatabaseConfig,
+    pub engine: Option<EngineConfig>,
+    pub history: Option<HistoryConfig>,
+    pub secret: SecretConfig,
 }
 
 impl AppConfig {
@@ -37,3 +78,15 @@ impl AppConfig {
         config
     }
 }
+
+impl SecretConfig {
+    pub fn previous_keys(&self) -> Vec<String> {
+        self.previous_keys
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect()
+    }
+}
@@ -67,6 +67,12 @@ pub enum Code {
     ValidationEmail = 1010,
     // 无效（未知的校验错误）
     ValidationUnknown = 1011,
+    // 必须是可选值之一
+    ValidationEnum = 1012,
+    // 格式不匹配
+    ValidationPattern = 1013,
+    // 类型不匹配
+    ValidationType = 1014,
 
     // 文件夹相关
     // 父文件夹不存在
@@ -82,6 +88,15 @@ pub enum Code {
     AppParentFolderNotExist = 3201, // 所属目录不存在
     AppNotExist = 3202,             // 应用不存在
     AppDraftNotExist = 3203,        // 应用草稿不存在
+    AppSpecInvalid = 3204,          // 应用草稿校验失败
+    AppVersionNotExist = 3205,      // 应用版本不存在
+    AppNodeNotExist = 3206,         // 草稿中不存在该节点
+    AppRunNotExist = 3207,          // 运行记录不存在
+    DebugSessionNotExist = 3208,    // 调试会话不存在或已过期
+    DebugBreakpointInvalid = 3209,  // 断点所在的节点不存在
+    AppVersionInvalid = 3210,       // 版本号不是合法的语义化版本
+    AppVersionDuplicate = 3211,     // 版本号已发布
+    AppVersionRegression = 3212,    // 版本号低于已发布的最高版本
 
     // 工作空间相关
     // 不能删除当前工作空间
@@ -94,6 +109,86 @@ pub enum Code {
     // 标签相关
     // 标签不存在
     TagNotExist = 3401,
+
+    // 应用草稿校验相关
+    // 草稿不是合法的文档
+    SpecMalformed = 3501,
+    // 节点数据不合法
+    SpecNodeInvalid = 3502,
+    // 节点类型未知
+    SpecNodeTypeUnknown = 3503,
+    // 节点 ID 重复
+    SpecNodeDuplicate = 3504,
+    // 连线数据不合法
+    SpecEdgeInvalid = 3505,
+    // 缺少开始节点
+    SpecStartMissing = 3506,
+    // 存在多个开始节点
+    SpecStartMultiple = 3507,
+    // 没有可到达的结束节点
+    SpecEndUnreachable = 3508,
+    // 连线的起始节点不存在
+    SpecEdgeSourceMissing = 3509,
+    // 连线的目标节点不存在
+    SpecEdgeTargetMissing = 3510,
+    // 连线的出口不存在
+    SpecEdgeHandleMissing = 3511,
+    // 节点无法从开始节点到达
+    SpecNodeOrphan = 3512,
+    // 循环体之外存在环
+    SpecCycle = 3513,
+    // 继续/终止循环节点不在循环内
+    SpecLoopControlOutside = 3514,
+    // 所属容器节点不存在
+    SpecParentInvalid = 3515,
+    // 子流程引用的应用不存在或未发布
+    SpecSubflowMissing = 3516,
+    // 子流程循环引用
+    SpecSubflowCycle = 3517,
+    // 引用的变量在节点处不可见
+    SpecVariableUnavailable = 3518,
+    // 开始节点不能放在容器内
+    SpecStartNested = 3519,
+
+    // 流程执行相关
+    // 缺少开始节点
+    EngineStartMissing = 3601,
+    // 节点类型暂不支持执行
+    EngineNodeUnsupported = 3602,
+    // 节点执行失败
+    EngineNodeFailed = 3603,
+    // 流程结束但未到达结束节点
+    EngineEndNotReached = 3604,
+    // 循环次数超出上限
+    EngineLoopLimit = 3605,
+    // 代码节点执行超时
+    EngineScriptTimeout = 3606,
+    // 代码节点超出内存限制
+    EngineScriptMemory = 3607,
+    // 子流程循环调用
+    EngineSubflowCycle = 3608,
+    // 子流程嵌套层数超出上限
+    EngineSubflowDepth = 3609,
+
+    // 数据源相关
+    // 数据库连接不存在
+    DatasourceNotExist = 3701,
+    // 连接标识符已存在
+    DatasourceKeyDuplicate = 3702,
+    // 数据库类型暂不支持
+    DatasourceTypeUnsupported = 3703,
+    // 连接地址不合法
+    DatasourceUrlInvalid = 3704,
+    // 连接数据库失败
+    DatasourceConnectFailed = 3705,
+    // 连接数据库超时
+    DatasourceConnectTimeout = 3706,
+    // 连接地址指向内部地址
+    DatasourceHostForbidden = 3707,
+
+    // API 密钥相关
+    // API 密钥不存在
+    ApiKeyNotExist = 3801,
 }
 
 impl From<Code> for i32 {
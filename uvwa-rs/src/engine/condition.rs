@@ -0,0 +1,264 @@
+use crate::engine::vars::Scope;
+use crate::models::spec::node::{CaseNode, CompareOpr, Condition, HANDLE_ELSE, LogicOpr};
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+// 选择第一个满足条件的分支，返回其出口 handle，均不满足时走 ELSE
+pub fn select_case(node: &CaseNode, scope: &Scope) -> String {
+    for (index, case) in node.cases.iter().enumerate() {
+        // 未配置条件的分支不参与匹配
+        if case.conditions.is_empty() {
+            continue;
+        }
+        let mut results = case.conditions.iter().map(|c| eval_condition(c, scope));
+        let matched = match case.opr {
+            LogicOpr::And => results.all(|r| r),
+            LogicOpr::Or => results.any(|r| r),
+        };
+        if matched {
+            return CaseNode::case_handle(index, &case.id);
+        }
+    }
+    HANDLE_ELSE.to_string()
+}
+
+pub fn eval_condition(cond: &Condition, scope: &Scope) -> bool {
+    let left = scope.resolve(cond.var_id.as_deref());
+    compare(cond.opr, &left, &cond.value)
+}
+
+// 左值为变量表中的值，已按 VariableKind 转换为对应的 JSON 类型（数值、布尔、对象、数组、字符串）
+// 右值为编辑器中输入的文本，按左值的类型解释
+pub fn compare(opr: CompareOpr, left: &Value, right: &str) -> bool {
+    match opr {
+        CompareOpr::Eq => equals(left, right),
+        CompareOpr::Ne => !equals(left, right),
+        CompareOpr::Gt => order(left, right).is_some_and(|o| o.is_gt()),
+        CompareOpr::Lt => order(left, right).is_some_and(|o| o.is_lt()),
+        CompareOpr::Ge => order(left, right).is_some_and(|o| o.is_ge()),
+        CompareOpr::Le => order(left, right).is_some_and(|o| o.is_le()),
+        CompareOpr::In => contains(left, right),
+        CompareOpr::NotIn => !contains(left, right),
+        CompareOpr::OneOf => within(left, right),
+        CompareOpr::NotOneOf => !within(left, right),
+        CompareOpr::StartWith => match left {
+            Value::Array(items) => items.first().is_some_and(|v| equals(v, right)),
+            v => text(v).is_some_and(|t| t.starts_with(right)),
+        },
+        CompareOpr::EndWith => match left {
+            Value::Array(items) => items.last().is_some_and(|v| equals(v, right)),
+            v => text(v).is_some_and(|t| t.ends_with(right)),
+        },
+        CompareOpr::IsEmpty => is_empty(left),
+        CompareOpr::IsNotEmpty => !is_empty(left),
+        // 正则不合法时视为不匹配
+        CompareOpr::Regex => Regex::new(right).is_ok_and(|re| match left {
+            Value::Array(items) => items.iter().filter_map(text).any(|t| re.is_match(&t)),
+            v => text(v).is_some_and(|t| re.is_match(&t)),
+        }),
+    }
+}
+
+// 标量的文本形式，null、对象、数组没有文本形式
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(a) => a.is_empty(),
+        Value::Object(o) => o.is_empty(),
+        _ => false,
+    }
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.trim().parse::<f64>().ok()
+}
+
+fn equals(left: &Value, right: &str) -> bool {
+    match left {
+        Value::Null => right.is_empty(),
+        Value::String(s) => s == right,
+        Value::Number(n) => n
+            .as_f64()
+            .zip(parse_number(right))
+            .is_some_and(|(l, r)| l == r),
+        Value::Bool(b) => right.trim().eq_ignore_ascii_case(&b.to_string()),
+        // 对象、数组与右值解析出的 JSON 比较
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::from_str::<Value>(right).is_ok_and(|r| &r == left)
+        }
+    }
+}
+
+// 数值按大小比较；字符串两侧都是数字时按数值比较，否则按字典序（适用于日期、时间文本）
+fn order(left: &Value, right: &str) -> Option<Ordering> {
+    match left {
+        Value::Number(n) => n.as_f64()?.partial_cmp(&parse_number(right)?),
+        Value::String(s) => match (parse_number(s), parse_number(right)) {
+            (Some(l), Some(r)) => l.partial_cmp(&r),
+            _ => Some(s.as_str().cmp(right)),
+        },
+        _ => None,
+    }
+}
+
+// 左值属于右值列表：右值为 JSON 数组或逗号分隔的文本；左值为数组时要求每个元素都属于列表
+fn within(left: &Value, right: &str) -> bool {
+    let options: Vec<String> = match serde_json::from_str::<Vec<Value>>(right) {
+        Ok(items) => items.iter().filter_map(text).collect(),
+        Err(_) => right.split(',').map(|o| o.trim().to_string()).collect(),
+    };
+    let one_of = |v: &Value| options.iter().any(|o| equals(v, o));
+    match left {
+        Value::Null | Value::Object(_) => false,
+        Value::Array(items) => !items.is_empty() && items.iter().all(one_of),
+        v => one_of(v),
+    }
+}
+
+// 标量的文本包含子串、数组包含元素、对象包含 key
+fn contains(left: &Value, right: &str) -> bool {
+    match left {
+        Value::Array(items) => items.iter().any(|v| equals(v, right)),
+        Value::Object(map) => map.contains_key(right),
+        v => text(v).is_some_and(|t| t.contains(right)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::spec::node::Case;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare() {
+        use CompareOpr::*;
+
+        let table = vec![
+            // 相等
+            (json!("abc"), Eq, "abc", true),
+            (json!("abc"), Eq, "ABC", false),
+            (json!(18), Eq, "18", true),
+            (json!(18), Eq, "18.0", true),
+            (json!(1.5), Eq, "1.50", true),
+            (json!(18), Eq, "abc", false),
+            (json!(true), Eq, "true", true),
+            (json!(false), Eq, "TRUE", false),
+            (json!({"a": 1}), Eq, r#"{"a":1}"#, true),
+            (json!([1, 2]), Eq, "[1,2]", true),
+            (json!(null), Eq, "", true),
+            (json!(null), Ne, "x", true),
+            (json!("abc"), Ne, "abd", true),
+            // 比较
+            (json!(20), Gt, "18", true),
+            (json!(18), Gt, "18", false),
+            (json!(18), Ge, "18", true),
+            (json!(2.5), Lt, "10", true),
+            (json!(10), Le, "9.9", false),
+            (json!("10"), Gt, "9", true),
+            (json!("2024-05-01"), Lt, "2024-06-01", true),
+            (json!(18), Gt, "abc", false),
+            (json!(null), Lt, "1", false),
+            (json!(true), Gt, "false", false),
+            // 属于
+            (json!("b"), OneOf, "a,b,c", true),
+            (json!("d"), OneOf, "a, b, c", false),
+            (json!(2), OneOf, "1, 2", true),
+            (json!(2), OneOf, "[1, 2]", true),
+            (json!(["a", "c"]), OneOf, "a,b,c", true),
+            (json!(["a", "d"]), OneOf, "a,b,c", false),
+            (json!([]), OneOf, "a", false),
+            (json!(null), OneOf, "a", false),
+            (json!("d"), NotOneOf, "a,b,c", true),
+            (json!(null), NotOneOf, "a", true),
+            // 包含
+            (json!("hello world"), In, "world", true),
+            (json!(["a", "b"]), In, "b", true),
+            (json!([1, 2]), In, "2", true),
+            (json!([1, 2]), In, "3", false),
+            (json!({"name": "uvwa"}), In, "name", true),
+            (json!(12), In, "1", true),
+            (json!(null), In, "a", false),
+            (json!("hello"), NotIn, "x", true),
+            // 开头、结尾
+            (json!("hello"), StartWith, "he", true),
+            (json!("hello"), EndWith, "lo", true),
+            (json!(12345), StartWith, "123", true),
+            (json!(["a", "b"]), StartWith, "a", true),
+            (json!(["a", "b"]), EndWith, "a", false),
+            (json!(null), StartWith, "", false),
+            // 为空
+            (json!(null), IsEmpty, "", true),
+            (json!(""), IsEmpty, "", true),
+            (json!([]), IsEmpty, "", true),
+            (json!({}), IsEmpty, "", true),
+            (json!(0), IsEmpty, "", false),
+            (json!(false), IsEmpty, "", false),
+            (json!("x"), IsNotEmpty, "", true),
+            // 正则
+            (json!("13800138000"), Regex, r"^1\d{10}$", true),
+            (json!("abc"), Regex, r"^\d+$", false),
+            (json!(42), Regex, r"^\d+$", true),
+            (json!(["x", "42"]), Regex, r"^\d+$", true),
+            (json!("abc"), Regex, "(", false),
+            (json!(null), Regex, ".*", false),
+        ];
+
+        for (left, opr, right, expected) in table {
+            assert_eq!(
+                compare(opr, &left, right),
+                expected,
+                "{} {:?} {:?}",
+                left,
+                opr,
+                right
+            );
+        }
+    }
+
+    #[test]
+    fn test_select_case() {
+        let node = CaseNode {
+            cases: serde_json::from_value::<Vec<Case>>(json!([
+                {"id": "empty", "conditions": []},
+                {"id": "adult", "opr": "and", "conditions": [
+                    {"varId": "age", "opr": ">=", "value": "18"},
+                    {"varId": "vip", "opr": "=", "value": "true"}
+                ]},
+                {"id": "any", "opr": "or", "conditions": [
+                    {"varId": "age", "opr": ">=", "value": "60"},
+                    {"varId": "tags", "opr": "in", "value": "gold"}
+                ]}
+            ]))
+            .unwrap(),
+            ..Default::default()
+        };
+
+        let table = vec![
+            (json!(20), json!(true), json!([]), "elif-adult"),
+            (json!(70), json!(true), json!([]), "elif-adult"),
+            (json!(70), json!(false), json!([]), "elif-any"),
+            (json!(10), json!(false), json!(["gold"]), "elif-any"),
+            (json!(10), json!(false), json!(["silver"]), HANDLE_ELSE),
+        ];
+
+        for (age, vip, tags, expected) in table {
+            let mut scope = Scope::default();
+            scope.set("age", age);
+            scope.set("vip", vip);
+            scope.set("tags", tags);
+            assert_eq!(select_case(&node, &scope), expected);
+        }
+    }
+}
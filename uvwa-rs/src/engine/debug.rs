@@ -0,0 +1,11 @@
+use crate::engine::trace::NodeTrace;
+use crate::engine::vars::Scope;
+use crate::models::spec::Node;
+use async_trait::async_trait;
+
+// 调试运行的控制，由业务层实现断点和单步：每个节点执行前调用，返回后才执行该节点，
+// 暂停期间可查看当前的变量表和已执行节点的记录
+#[async_trait]
+pub trait Debugger: Send + Sync {
+    async fn before_node(&self, node: &Node, scope: &Scope, trace: &[NodeTrace]);
+}
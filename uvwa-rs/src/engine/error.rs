@@ -0,0 +1,93 @@
+use crate::core::code::Code;
+use crate::web::error::WebError;
+use thiserror::Error;
+use validator::ValidationErrors;
+
+#[derive(Error, Debug)]
+pub enum EngineError {
+    #[error("start node not found")]
+    StartMissing,
+    #[error("invalid input: {0}")]
+    Input(ValidationErrors),
+    #[error("node {node_id} of type {node_type} cannot be executed alone")]
+    NodeUnsupported { node_id: String, node_type: String },
+    #[error("node {node_id} failed: {message}")]
+    NodeFailed { node_id: String, message: String },
+    #[error("flow finished without reaching an end node")]
+    EndNotReached,
+    #[error("loop {node_id} exceeded {limit} iterations")]
+    LoopLimit { node_id: String, limit: u64 },
+    #[error("script of node {node_id} timed out after {timeout}s")]
+    ScriptTimeout { node_id: String, timeout: u64 },
+    #[error("script of node {node_id} exceeded the memory limit")]
+    ScriptMemory { node_id: String },
+    #[error("subflow {flow_id} of node {node_id} is already in the call chain")]
+    SubflowCycle { node_id: String, flow_id: String },
+    #[error("subflow of node {node_id} exceeded the depth limit {limit}")]
+    SubflowDepth { node_id: String, limit: u64 },
+}
+
+impl EngineError {
+    pub fn node_failed(node_id: &str, message: impl Into<String>) -> Self {
+        Self::NodeFailed {
+            node_id: node_id.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn code(&self) -> Code {
+        match self {
+            Self::StartMissing => Code::EngineStartMissing,
+            Self::Input(_) => Code::IllegalParam,
+            Self::NodeUnsupported { .. } => Code::EngineNodeUnsupported,
+            Self::NodeFailed { .. } => Code::EngineNodeFailed,
+            Self::EndNotReached => Code::EngineEndNotReached,
+            Self::LoopLimit { .. } => Code::EngineLoopLimit,
+            Self::ScriptTimeout { .. } => Code::EngineScriptTimeout,
+            Self::ScriptMemory { .. } => Code::EngineScriptMemory,
+            Self::SubflowCycle { .. } => Code::EngineSubflowCycle,
+            Self::SubflowDepth { .. } => Code::EngineSubflowDepth,
+        }
+    }
+
+    pub fn args(&self) -> Vec<(String, String)> {
+        match self {
+            Self::NodeFailed { node_id, message } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("detail".to_string(), message.clone()),
+            ],
+            Self::LoopLimit { node_id, limit } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("limit".to_string(), limit.to_string()),
+            ],
+            Self::ScriptTimeout { node_id, timeout } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("timeout".to_string(), timeout.to_string()),
+            ],
+            Self::NodeUnsupported { node_id, node_type } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("type".to_string(), node_type.clone()),
+            ],
+            Self::ScriptMemory { node_id } => vec![("node".to_string(), node_id.clone())],
+            Self::SubflowCycle { node_id, flow_id } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("flow".to_string(), flow_id.clone()),
+            ],
+            Self::SubflowDepth { node_id, limit } => vec![
+                ("node".to_string(), node_id.clone()),
+                ("limit".to_string(), limit.to_string()),
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+impl From<EngineError> for WebError {
+    fn from(err: EngineError) -> Self {
+        match err {
+            // 输入校验错误沿用参数校验的字段错误格式
+            EngineError::Input(errors) => WebError::Val(errors),
+            err => WebError::BizWithArgs(err.code().into(), err.args()),
+        }
+    }
+}
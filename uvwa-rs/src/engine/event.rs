@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+// 运行过程中的事件，流式运行时逐个推送给调用方
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum RunEvent {
+    NodeStarted {
+        node_id: String,
+        node_type: &'static str,
+    },
+    NodeFinished {
+        node_id: String,
+        // 执行耗时（毫秒），容器节点包含其内部节点
+        elapsed: u64,
+    },
+    // 结束节点的文本片段，对话应用逐段展示
+    Text {
+        delta: String,
+    },
+}
+
+impl RunEvent {
+    // SSE 的事件名
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NodeStarted { .. } => "node_started",
+            Self::NodeFinished { .. } => "node_finished",
+            Self::Text { .. } => "text",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum CharClass {
+    Word,
+    Space,
+    Other,
+}
+
+fn class(c: char) -> CharClass {
+    if c.is_ascii_alphanumeric() || c == '_' || c == '\'' {
+        CharClass::Word
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else {
+        CharClass::Other
+    }
+}
+
+// 把文本切分为近似 token 的片段：英文单词连同其后的空白为一段，中文、标点等每个字符为一段
+pub fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev = CharClass::Space;
+    for (i, c) in text.char_indices() {
+        let current = class(c);
+        let split =
+            current != CharClass::Space && !(prev == CharClass::Word && current == CharClass::Word);
+        if split && i > start {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        prev = current;
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let table = vec![
+            ("hello  world!", vec!["hello  ", "world", "!"]),
+            ("你好，uvwa", vec!["你", "好", "，", "uvwa"]),
+            ("  lead", vec!["  ", "lead"]),
+            ("", vec![]),
+        ];
+        for (text, expected) in table {
+            assert_eq!(tokens(text), expected, "{}", text);
+            assert_eq!(tokens(text).concat(), text);
+        }
+    }
+}
@@ -0,0 +1,609 @@
+use crate::engine::vars::Scope;
+use crate::models::spec::token::{Token, tokenize};
+use serde_json::{Number, Value};
+
+// 表达式长度和嵌套层数上限，防止异常输入耗尽栈空间
+const MAX_LEN: usize = 4096;
+const MAX_DEPTH: usize = 64;
+
+// 赋值节点使用的表达式：字面量、变量引用 {{id}}、成员访问、算术/比较/逻辑运算和少量内置函数，
+// 只能读取变量表，没有循环和赋值，例如：{{count}} + 1、upper({{user}}.name)、len({{items}}) > 0
+pub fn eval(expr: &str, scope: &Scope) -> Result<Value, String> {
+    if expr.len() > MAX_LEN {
+        return Err(format!("expression is longer than {} characters", MAX_LEN));
+    }
+    let mut parser = Parser {
+        tokens: tokenize(expr)?,
+        pos: 0,
+        depth: 0,
+    };
+    let ast = parser.expr()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(format!("unexpected {}", token));
+    }
+    ast.eval(scope)
+}
+
+#[derive(Debug)]
+enum Ast {
+    Lit(Value),
+    Ref(String),
+    Array(Vec<Ast>),
+    Member(Box<Ast>, String),
+    Index(Box<Ast>, Box<Ast>),
+    Unary(&'static str, Box<Ast>),
+    Binary(&'static str, Box<Ast>, Box<Ast>),
+    Call(String, Vec<Ast>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek_op(&self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) if ops.contains(op) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &'static str) -> Result<(), String> {
+        match self.peek_op(&[op]) {
+            Some(_) => {
+                self.pos += 1;
+                Ok(())
+            }
+            None => match self.tokens.get(self.pos) {
+                Some(token) => Err(format!("expected '{}' but found {}", op, token)),
+                None => Err(format!("expected '{}'", op)),
+            },
+        }
+    }
+
+    fn expr(&mut self) -> Result<Ast, String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("expression is nested too deeply".to_string());
+        }
+        let ast = self.binary(0);
+        self.depth -= 1;
+        ast
+    }
+
+    // 按优先级从低到高：|| && 相等 比较 加减 乘除
+    fn binary(&mut self, level: usize) -> Result<Ast, String> {
+        const LEVELS: [&[&str]; 6] = [
+            &["||"],
+            &["&&"],
+            &["==", "!="],
+            &["<", "<=", ">", ">="],
+            &["+", "-"],
+            &["*", "/", "%"],
+        ];
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.peek_op(ops) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Ast::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Ast, String> {
+        match self.peek_op(&["-", "!"]) {
+            Some(op) => {
+                self.pos += 1;
+                self.depth += 1;
+                if self.depth > MAX_DEPTH {
+                    return Err("expression is nested too deeply".to_string());
+                }
+                let operand = self.unary()?;
+                self.depth -= 1;
+                Ok(Ast::Unary(op, Box::new(operand)))
+            }
+            None => self.postfix(),
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Ast, String> {
+        let mut ast = self.primary()?;
+        loop {
+            if self.peek_op(&["."]).is_some() {
+                self.pos += 1;
+                match self.tokens.get(self.pos) {
+                    Some(Token::Ident(name)) => {
+                        ast = Ast::Member(Box::new(ast), name.clone());
+                        self.pos += 1;
+                    }
+                    _ => return Err("expected a field name after '.'".to_string()),
+                }
+            } else if self.peek_op(&["["]).is_some() {
+                self.pos += 1;
+                let index = self.expr()?;
+                self.expect("]")?;
+                ast = Ast::Index(Box::new(ast), Box::new(index));
+            } else {
+                return Ok(ast);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Ast, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or("unexpected end of expression")?;
+        self.pos += 1;
+        match token {
+            Token::Lit(value) => Ok(Ast::Lit(value)),
+            Token::Ref(reference) => Ok(Ast::Ref(reference)),
+            Token::Ident(name) => {
+                self.expect("(")?;
+                let args = self.list(")")?;
+                Ok(Ast::Call(name, args))
+            }
+            Token::Op("(") => {
+                let ast = self.expr()?;
+                self.expect(")")?;
+                Ok(ast)
+            }
+            Token::Op("[") => Ok(Ast::Array(self.list("]")?)),
+            token => Err(format!("unexpected {}", token)),
+        }
+    }
+
+    // 逗号分隔的表达式列表，直到 close 为止
+    fn list(&mut self, close: &'static str) -> Result<Vec<Ast>, String> {
+        let mut items = Vec::new();
+        if self.peek_op(&[close]).is_some() {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expr()?);
+            if self.peek_op(&[","]).is_some() {
+                self.pos += 1;
+            } else {
+                self.expect(close)?;
+                return Ok(items);
+            }
+        }
+    }
+}
+
+impl Ast {
+    fn eval(&self, scope: &Scope) -> Result<Value, String> {
+        match self {
+            Self::Lit(value) => Ok(value.clone()),
+            Self::Ref(reference) => Ok(scope.resolve(Some(reference))),
+            Self::Array(items) => items
+                .iter()
+                .map(|item| item.eval(scope))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            Self::Member(target, name) => match target.eval(scope)? {
+                Value::Object(mut map) => Ok(map.remove(name).unwrap_or(Value::Null)),
+                Value::Null => Ok(Value::Null),
+                value => Err(format!("{} has no field {}", type_name(&value), name)),
+            },
+            Self::Index(target, index) => match (target.eval(scope)?, index.eval(scope)?) {
+                (Value::Array(mut items), Value::Number(n)) => {
+                    let index = n
+                        .as_u64()
+                        .ok_or("array index must be a non-negative integer")?;
+                    Ok(match usize::try_from(index) {
+                        Ok(i) if i < items.len() => items.swap_remove(i),
+                        _ => Value::Null,
+                    })
+                }
+                (Value::Object(mut map), Value::String(key)) => {
+                    Ok(map.remove(&key).unwrap_or(Value::Null))
+                }
+                (Value::Null, _) => Ok(Value::Null),
+                (value, _) => Err(format!("{} cannot be indexed", type_name(&value))),
+            },
+            Self::Unary("-", operand) => match num(&operand.eval(scope)?) {
+                Some(Num::Int(i)) => i.checked_neg().map(Value::from).ok_or_else(overflow),
+                Some(Num::Float(f)) => float(-f),
+                None => Err("'-' requires a number".to_string()),
+            },
+            Self::Unary(_, operand) => Ok(Value::Bool(!truthy(&operand.eval(scope)?))),
+            // 逻辑运算短路求值
+            Self::Binary("&&", left, right) => Ok(Value::Bool(
+                truthy(&left.eval(scope)?) && truthy(&right.eval(scope)?),
+            )),
+            Self::Binary("||", left, right) => Ok(Value::Bool(
+                truthy(&left.eval(scope)?) || truthy(&right.eval(scope)?),
+            )),
+            Self::Binary(op, left, right) => binary(op, left.eval(scope)?, right.eval(scope)?),
+            Self::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(scope))
+                    .collect::<Result<Vec<_>, _>>()?;
+                call(name, args)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+}
+
+fn num(value: &Value) -> Option<Num> {
+    let Value::Number(n) = value else {
+        return None;
+    };
+    n.as_i64()
+        .map(Num::Int)
+        .or_else(|| n.as_f64().map(Num::Float))
+}
+
+fn float(f: f64) -> Result<Value, String> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| "result is not a finite number".to_string())
+}
+
+fn overflow() -> String {
+    "integer overflow".to_string()
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+// 标量的文本形式，拼接字符串时使用
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn binary(op: &str, left: Value, right: Value) -> Result<Value, String> {
+    match (op, num(&left), num(&right)) {
+        ("==", Some(l), Some(r)) => return Ok(Value::Bool(l.as_f64() == r.as_f64())),
+        ("!=", Some(l), Some(r)) => return Ok(Value::Bool(l.as_f64() != r.as_f64())),
+        (_, Some(l), Some(r)) => return arithmetic(op, l, r),
+        _ => {}
+    }
+    match (op, left, right) {
+        ("==", l, r) => Ok(Value::Bool(l == r)),
+        ("!=", l, r) => Ok(Value::Bool(l != r)),
+        ("+", Value::Array(mut l), Value::Array(r)) => {
+            l.extend(r);
+            Ok(Value::Array(l))
+        }
+        ("+", l @ Value::String(_), r) | ("+", l, r @ Value::String(_)) => {
+            Ok(Value::String(text(&l) + &text(&r)))
+        }
+        ("<" | "<=" | ">" | ">=", Value::String(l), Value::String(r)) => {
+            Ok(Value::Bool(compare(op, l.cmp(&r))))
+        }
+        (op, l, r) => Err(format!(
+            "'{}' is not supported between {} and {}",
+            op,
+            type_name(&l),
+            type_name(&r)
+        )),
+    }
+}
+
+fn compare(op: &str, ordering: std::cmp::Ordering) -> bool {
+    match op {
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        _ => ordering.is_ge(),
+    }
+}
+
+// 整数运算溢出时报错；整数相除不能整除时结果为小数
+fn arithmetic(op: &str, left: Num, right: Num) -> Result<Value, String> {
+    if let (Num::Int(l), Num::Int(r)) = (left, right) {
+        let result = match op {
+            "+" => l.checked_add(r),
+            "-" => l.checked_sub(r),
+            "*" => l.checked_mul(r),
+            "/" | "%" if r == 0 => return Err("division by zero".to_string()),
+            // i64::MIN / -1 的余数同样溢出，交给 checked_div 报错
+            "/" if l.checked_rem(r).is_none_or(|m| m == 0) => l.checked_div(r),
+            "/" => return float(l as f64 / r as f64),
+            "%" => l.checked_rem(r),
+            op => {
+                return Ok(Value::Bool(compare(op, l.cmp(&r))));
+            }
+        };
+        return result.map(Value::from).ok_or_else(overflow);
+    }
+
+    let (l, r) = (left.as_f64(), right.as_f64());
+    match op {
+        "+" => float(l + r),
+        "-" => float(l - r),
+        "*" => float(l * r),
+        "/" | "%" if r == 0.0 => Err("division by zero".to_string()),
+        "/" => float(l / r),
+        "%" => float(l % r),
+        op => Ok(Value::Bool(
+            l.partial_cmp(&r).is_some_and(|o| compare(op, o)),
+        )),
+    }
+}
+
+// 内置函数
+fn call(name: &str, args: Vec<Value>) -> Result<Value, String> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(format!("{}() takes {} argument(s)", name, n))
+        }
+    };
+    let number = |value: &Value| num(value).ok_or_else(|| format!("{}() requires a number", name));
+    let string = |value: &Value| match value {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(format!("{}() requires a string", name)),
+    };
+
+    match name {
+        "len" => {
+            arity(1)?;
+            match &args[0] {
+                Value::String(s) => Ok(Value::from(s.chars().count())),
+                Value::Array(a) => Ok(Value::from(a.len())),
+                Value::Object(o) => Ok(Value::from(o.len())),
+                Value::Null => Ok(Value::from(0)),
+                value => Err(format!("len() is not supported for {}", type_name(value))),
+            }
+        }
+        "upper" => {
+            arity(1)?;
+            Ok(Value::String(string(&args[0])?.to_uppercase()))
+        }
+        "lower" => {
+            arity(1)?;
+            Ok(Value::String(string(&args[0])?.to_lowercase()))
+        }
+        "trim" => {
+            arity(1)?;
+            Ok(Value::String(string(&args[0])?.trim().to_string()))
+        }
+        "str" => {
+            arity(1)?;
+            Ok(Value::String(text(&args[0])))
+        }
+        "number" => {
+            arity(1)?;
+            match &args[0] {
+                Value::Number(_) => Ok(args[0].clone()),
+                Value::String(s) => match s.trim().parse::<i64>() {
+                    Ok(i) => Ok(Value::from(i)),
+                    Err(_) => s
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("{} is not a number", s))
+                        .and_then(float),
+                },
+                value => Err(format!(
+                    "{} cannot be converted to a number",
+                    type_name(value)
+                )),
+            }
+        }
+        "abs" => {
+            arity(1)?;
+            match number(&args[0])? {
+                Num::Int(i) => i.checked_abs().map(Value::from).ok_or_else(overflow),
+                Num::Float(f) => float(f.abs()),
+            }
+        }
+        "floor" | "ceil" => {
+            arity(1)?;
+            let f = number(&args[0])?.as_f64();
+            let f = if name == "floor" { f.floor() } else { f.ceil() };
+            integral(f)
+        }
+        // round(x) 取整，round(x, n) 保留 n 位小数
+        "round" => {
+            let digits = match args.len() {
+                1 => 0,
+                2 => match number(&args[1])? {
+                    Num::Int(n) if (0..=15).contains(&n) => n as i32,
+                    _ => return Err("round() digits must be between 0 and 15".to_string()),
+                },
+                _ => return Err("round() takes 1 or 2 arguments".to_string()),
+            };
+            let f = number(&args[0])?.as_f64();
+            if digits == 0 {
+                integral(f.round())
+            } else {
+                let factor = 10f64.powi(digits);
+                float((f * factor).round() / factor)
+            }
+        }
+        "min" | "max" => {
+            // 也可以传入一个数组
+            let items = match args.as_slice() {
+                [Value::Array(items)] => items.clone(),
+                _ => args.clone(),
+            };
+            let mut best: Option<(Num, Value)> = None;
+            for item in items {
+                let n = number(&item)?;
+                let better = best.as_ref().is_none_or(|(b, _)| {
+                    if name == "min" {
+                        n.as_f64() < b.as_f64()
+                    } else {
+                        n.as_f64() > b.as_f64()
+                    }
+                });
+                if better {
+                    best = Some((n, item));
+                }
+            }
+            Ok(best.map_or(Value::Null, |(_, v)| v))
+        }
+        "contains" => {
+            arity(2)?;
+            match (&args[0], &args[1]) {
+                (Value::String(s), Value::String(sub)) => Ok(Value::Bool(s.contains(sub.as_str()))),
+                (Value::Array(items), value) => Ok(Value::Bool(items.contains(value))),
+                (Value::Object(map), Value::String(key)) => Ok(Value::Bool(map.contains_key(key))),
+                (Value::Null, _) => Ok(Value::Bool(false)),
+                (value, _) => Err(format!(
+                    "contains() is not supported for {}",
+                    type_name(value)
+                )),
+            }
+        }
+        "join" => {
+            arity(2)?;
+            let Value::Array(items) = &args[0] else {
+                return Err("join() requires an array".to_string());
+            };
+            let sep = string(&args[1])?;
+            Ok(Value::String(
+                items.iter().map(text).collect::<Vec<_>>().join(&sep),
+            ))
+        }
+        "split" => {
+            arity(2)?;
+            let (s, sep) = (string(&args[0])?, string(&args[1])?);
+            if sep.is_empty() {
+                return Err("split() separator must not be empty".to_string());
+            }
+            Ok(Value::Array(
+                s.split(sep.as_str()).map(Value::from).collect(),
+            ))
+        }
+        name => Err(format!("unknown function {}", name)),
+    }
+}
+
+// 取整后的小数在 i64 范围内时转为整数
+fn integral(f: f64) -> Result<Value, String> {
+    if f.is_finite() && f >= i64::MIN as f64 && f <= i64::MAX as f64 {
+        Ok(Value::from(f as i64))
+    } else {
+        float(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_eval() {
+        let mut scope = Scope::default();
+        scope.set("count", json!(2));
+        scope.set("user", json!({"name": "uvwa", "tags": ["a", "b"]}));
+        scope.set("price-1", json!(1.5));
+
+        let table = vec![
+            ("1 + 2 * 3", json!(7)),
+            ("(1 + 2) * 3", json!(9)),
+            ("7 / 2", json!(3.5)),
+            ("8 / 2", json!(4)),
+            ("-{{count}} % 3", json!(-2)),
+            ("{{count}} + {{price-1}}", json!(3.5)),
+            ("'n=' + {{count}}", json!("n=2")),
+            ("\"a\\\"b\" + 'c'", json!("a\"bc")),
+            ("{{user}}.name", json!("uvwa")),
+            ("{{user}}.tags[1]", json!("b")),
+            ("{{user}}['name']", json!("uvwa")),
+            ("{{user}}.missing.name", json!(null)),
+            ("{{missing}}", json!(null)),
+            ("{{user}}.tags + ['c']", json!(["a", "b", "c"])),
+            ("{{count}} >= 2 and not ({{count}} == 3)", json!(true)),
+            ("1 == 1.0", json!(true)),
+            ("'abc' < 'abd'", json!(true)),
+            ("upper({{user}}.name)", json!("UVWA")),
+            ("len({{user}}.tags) > 1 || false", json!(true)),
+            ("round(2.71828, 2)", json!(2.72)),
+            ("round(2.5) + floor(1.9) + ceil(1.1)", json!(6)),
+            ("max(1, {{count}}, 0.5)", json!(2)),
+            ("min([3, 1, 2])", json!(1)),
+            ("contains({{user}}.tags, 'a')", json!(true)),
+            ("join(split('a,b', ','), '-')", json!("a-b")),
+            ("number(' 42 ') + 1", json!(43)),
+            ("str(true) + str(null)", json!("true")),
+            ("[]", json!([])),
+        ];
+        for (expr, expected) in table {
+            assert_eq!(eval(expr, &scope).unwrap(), expected, "{}", expr);
+        }
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let scope = Scope::default();
+        let table = vec![
+            "1 +",
+            "1 / 0",
+            "9223372036854775807 + 1",
+            "(-9223372036854775807 - 1) / -1",
+            "(-9223372036854775807 - 1) % -1",
+            "'a' - 1",
+            "{{x",
+            "'abc",
+            "1 2",
+            "exec('rm')",
+            "len(1, 2)",
+            "true.name",
+            "1 # 2",
+        ];
+        for expr in table {
+            assert!(eval(expr, &scope).is_err(), "{}", expr);
+        }
+
+        // 逻辑运算短路，右侧不会求值
+        assert_eq!(eval("false && 1 / 0", &scope).unwrap(), json!(false));
+
+        let deep = format!("{}1{}", "(".repeat(100), ")".repeat(100));
+        assert!(eval(&deep, &scope).is_err());
+        assert!(eval(&"-".repeat(100), &scope).is_err());
+        assert!(eval(&"1+".repeat(3000), &scope).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::models::spec::node::StartNode;
+use crate::models::spec::variable::{Rule, RuleKind, RuleValue, Variable, VariableKind};
+use regex::Regex;
+use serde_json::{Map, Number, Value};
+use std::borrow::Cow;
+use validator::{ValidateEmail, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+// 按开始节点声明的变量类型转换调用参数并执行校验规则，返回「变量名 -> 值」
+// 所有变量的错误一并收集，字段名为变量名
+pub fn bind_input(
+    start: &StartNode,
+    input: &Map<String, Value>,
+) -> Result<Map<String, Value>, ValidationErrors> {
+    let mut values = Map::new();
+    let mut errors = ValidationErrors::new();
+
+    for var in &start.input {
+        let raw = input.get(&var.name).cloned().unwrap_or(Value::Null);
+        let value = match var.kind {
+            Some(kind) => match coerce(kind, raw) {
+                Some(value) => value,
+                None => {
+                    let mut err = ValidationError::new("type");
+                    err.add_param(Cow::from("kind"), &kind);
+                    add_errors(&mut errors, var, vec![err]);
+                    continue;
+                }
+            },
+            None => raw,
+        };
+
+        let errs: Vec<ValidationError> = var
+            .rules
+            .iter()
+            .filter_map(|rule| check_rule(rule, &value))
+            .collect();
+        if errs.is_empty() {
+            values.insert(var.name.clone(), value);
+        } else {
+            add_errors(&mut errors, var, errs);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(values)
+    } else {
+        Err(errors)
+    }
+}
+
+fn add_errors(errors: &mut ValidationErrors, var: &Variable, errs: Vec<ValidationError>) {
+    errors.errors_mut().insert(
+        Cow::Owned(var.name.clone()),
+        ValidationErrorsKind::Field(errs),
+    );
+}
+
+// 类型转换：表单提交的数字、布尔值可能是字符串，对象、数组可能是 JSON 字符串
+// 空字符串按未填写处理，无法转换时返回 None
+pub fn coerce(kind: VariableKind, value: Value) -> Option<Value> {
+    if value.is_null() || (kind != VariableKind::String && value.as_str() == Some("")) {
+        return Some(Value::Null);
+    }
+
+    match kind {
+        VariableKind::String => match value {
+            Value::String(_) => Some(value),
+            Value::Number(n) => Some(Value::String(n.to_string())),
+            Value::Bool(b) => Some(Value::String(b.to_string())),
+            _ => None,
+        },
+        VariableKind::Integer => as_i64(&value)
+            .and_then(|n| i32::try_from(n).ok())
+            .map(Value::from),
+        VariableKind::Long => as_i64(&value).map(Value::from),
+        VariableKind::Decimal => match &value {
+            Value::Number(_) => Some(value),
+            Value::String(s) => s
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number),
+            _ => None,
+        },
+        VariableKind::Boolean => match &value {
+            Value::Bool(_) => Some(value),
+            Value::String(s) => match s.trim() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        },
+        VariableKind::Object => match parse_json(value) {
+            value @ Value::Object(_) => Some(value),
+            _ => None,
+        },
+        // 文件变量保存地址或文件描述对象
+        kind if kind.is_file() => match value {
+            Value::String(_) | Value::Object(_) => Some(value),
+            _ => None,
+        },
+        kind => {
+            let Value::Array(items) = parse_json(value) else {
+                return None;
+            };
+            match kind.element() {
+                Some(element) => items
+                    .into_iter()
+                    .map(|item| coerce(element, item).filter(|v| !v.is_null()))
+                    .collect::<Option<Vec<_>>>()
+                    .map(Value::Array),
+                None => Some(Value::Array(items)),
+            }
+        }
+    }
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn parse_json(value: Value) -> Value {
+    match &value {
+        Value::String(s) => serde_json::from_str(s).unwrap_or(value),
+        _ => value,
+    }
+}
+
+// 执行单条规则，未填写的值只检查 required
+fn check_rule(rule: &Rule, value: &Value) -> Option<ValidationError> {
+    let empty = match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    };
+    if rule.kind == RuleKind::Required {
+        let required = !matches!(rule.value, Some(RuleValue::Bool(false)));
+        return (required && empty).then(|| with_message(ValidationError::new("required"), rule));
+    }
+    if value.is_null() {
+        return None;
+    }
+
+    let rule_text = rule.value.as_ref().map(|v| v.as_text()).unwrap_or_default();
+    let err = match rule.kind {
+        RuleKind::Length | RuleKind::Size => {
+            let (min, max) = parse_bounds(&rule_text);
+            let len = match value {
+                Value::String(s) => s.chars().count(),
+                Value::Array(items) => items.len(),
+                Value::Object(map) => map.len(),
+                _ => return None,
+            } as f64;
+            out_of_bounds(len, &min, &max).then(|| bounds_error("length", min, max))
+        }
+        RuleKind::Min | RuleKind::Max => {
+            let bound = parse_number(&rule_text)?;
+            let limit = bound.as_f64()?;
+            let invalid = numbers(value).into_iter().any(|n| match rule.kind {
+                RuleKind::Min => n < limit,
+                _ => n > limit,
+            });
+            let (min, max) = match rule.kind {
+                RuleKind::Min => (Some(bound), None),
+                _ => (None, Some(bound)),
+            };
+            invalid.then(|| bounds_error("range", min, max))
+        }
+        RuleKind::Enum => {
+            let options: Vec<&str> = rule_text
+                .split(',')
+                .map(|o| o.trim())
+                .filter(|o| !o.is_empty())
+                .collect();
+            let invalid =
+                !options.is_empty() && texts(value).iter().any(|t| !options.contains(&t.as_str()));
+            invalid.then(|| {
+                let mut err = ValidationError::new("enum");
+                err.add_param(Cow::from("options"), &options.join(", "));
+                err
+            })
+        }
+        RuleKind::Email => texts(value)
+            .iter()
+            .any(|t| !t.validate_email())
+            .then(|| ValidationError::new("email")),
+        // 正则本身不合法属于设计问题，这里不拦截调用
+        RuleKind::Pattern => Regex::new(&rule_text)
+            .ok()
+            .filter(|re| texts(value).iter().any(|t| !re.is_match(t)))
+            .map(|_| ValidationError::new("pattern")),
+        RuleKind::Required => None,
+    };
+    err.map(|e| with_message(e, rule))
+}
+
+fn with_message(err: ValidationError, rule: &Rule) -> ValidationError {
+    match rule.message.as_deref().filter(|m| !m.is_empty()) {
+        Some(message) => err.with_message(Cow::Owned(message.to_string())),
+        None => err,
+    }
+}
+
+// 「min,max」格式的范围，任一端可以为空
+fn parse_bounds(text: &str) -> (Option<Number>, Option<Number>) {
+    let (min, max) = text.split_once(',').unwrap_or((text, ""));
+    (parse_number(min), parse_number(max))
+}
+
+fn parse_number(text: &str) -> Option<Number> {
+    serde_json::from_str::<Number>(text.trim()).ok()
+}
+
+fn out_of_bounds(n: f64, min: &Option<Number>, max: &Option<Number>) -> bool {
+    min.as_ref().and_then(|m| m.as_f64()).is_some_and(|m| n < m)
+        || max.as_ref().and_then(|m| m.as_f64()).is_some_and(|m| n > m)
+}
+
+fn bounds_error(code: &'static str, min: Option<Number>, max: Option<Number>) -> ValidationError {
+    let mut err = ValidationError::new(code);
+    if let Some(min) = min {
+        err.add_param(Cow::from("min"), &min);
+    }
+    if let Some(max) = max {
+        err.add_param(Cow::from("max"), &max);
+    }
+    err
+}
+
+// 数组规则作用于每个元素
+fn numbers(value: &Value) -> Vec<f64> {
+    match value {
+        Value::Array(items) => items.iter().filter_map(|v| v.as_f64()).collect(),
+        v => v.as_f64().into_iter().collect(),
+    }
+}
+
+fn texts(value: &Value) -> Vec<String> {
+    let text = |v: &Value| match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    };
+    match value {
+        Value::Array(items) => items.iter().filter_map(text).collect(),
+        v => text(v).into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn start(input: Value) -> StartNode {
+        serde_json::from_value(json!({ "input": input })).unwrap()
+    }
+
+    fn error_codes(errors: &ValidationErrors, field: &str) -> Vec<String> {
+        errors.field_errors()[field]
+            .iter()
+            .map(|e| e.code.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_bind_input_coerce() {
+        let node = start(json!([
+            {"name": "age", "type": "INTEGER"},
+            {"name": "price", "type": "DECIMAL"},
+            {"name": "vip", "type": "BOOLEAN"},
+            {"name": "tags", "type": "ARRAY_LONG"},
+            {"name": "meta", "type": "OBJECT"},
+            {"name": "code", "type": "STRING"},
+            {"name": "missing", "type": "LONG"}
+        ]));
+        let input = json!({
+            "age": "18", "price": "9.5", "vip": "true", "tags": "[1, \"2\"]",
+            "meta": {"a": 1}, "code": 42, "extra": "ignored"
+        });
+
+        let values = bind_input(&node, input.as_object().unwrap()).unwrap();
+        assert_eq!(values["age"], json!(18));
+        assert_eq!(values["price"], json!(9.5));
+        assert_eq!(values["vip"], json!(true));
+        assert_eq!(values["tags"], json!([1, 2]));
+        assert_eq!(values["meta"], json!({"a": 1}));
+        assert_eq!(values["code"], json!("42"));
+        assert_eq!(values["missing"], Value::Null);
+        assert!(!values.contains_key("extra"));
+    }
+
+    #[test]
+    fn test_bind_input_rules() {
+        let node = start(json!([
+            {"name": "age", "type": "INTEGER", "rules": [
+                {"type": "required"}, {"type": "min", "value": "18"}, {"type": "max", "value": 60}]},
+            {"name": "name", "type": "STRING", "rules": [{"type": "length", "value": "2,4"}]},
+            {"name": "level", "type": "STRING", "rules": [{"type": "enum", "value": "a,b"}]},
+            {"name": "mail", "type": "STRING", "rules": [{"type": "email"}]},
+            {"name": "phone", "type": "STRING", "rules": [
+                {"type": "pattern", "value": "^\\d+$", "message": "digits only"}]},
+            {"name": "items", "type": "ARRAY_STRING", "rules": [{"type": "size", "value": ",1"}]},
+            {"name": "count", "type": "INTEGER"},
+            {"name": "note", "type": "STRING", "rules": [{"type": "required"}]},
+            {"name": "ok", "type": "STRING", "rules": [{"type": "length", "value": "1,"}]}
+        ]));
+        let input = json!({
+            "age": 12, "name": "uvwa-rs", "level": "c", "mail": "not-mail", "phone": "12a",
+            "items": ["x", "y"], "count": "abc", "note": "", "ok": "y"
+        });
+
+        let errors = bind_input(&node, input.as_object().unwrap()).unwrap_err();
+        assert_eq!(error_codes(&errors, "age"), vec!["range"]);
+        assert_eq!(error_codes(&errors, "name"), vec!["length"]);
+        assert_eq!(error_codes(&errors, "level"), vec!["enum"]);
+        assert_eq!(error_codes(&errors, "mail"), vec!["email"]);
+        assert_eq!(error_codes(&errors, "phone"), vec!["pattern"]);
+        assert_eq!(error_codes(&errors, "items"), vec!["length"]);
+        assert_eq!(error_codes(&errors, "count"), vec!["type"]);
+        assert_eq!(error_codes(&errors, "note"), vec!["required"]);
+        assert!(!errors.field_errors().contains_key("ok"));
+
+        let age = &errors.field_errors()["age"][0];
+        assert_eq!(age.params["min"], json!(18));
+        let phone = &errors.field_errors()["phone"][0];
+        assert_eq!(phone.message.as_deref(), Some("digits only"));
+    }
+}
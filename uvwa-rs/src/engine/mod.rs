@@ -0,0 +1,13 @@
+pub mod condition;
+pub mod debug;
+pub mod error;
+pub mod event;
+pub mod expr;
+pub mod input;
+pub mod response;
+pub mod runner;
+pub mod script;
+pub mod sql;
+pub mod subflow;
+pub mod trace;
+pub mod vars;
@@ -0,0 +1,184 @@
+use crate::core::code::Code;
+use crate::models::spec::node::EndNodeOutput;
+use crate::web::r::{R, translate};
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// 结束节点描述的调用响应，运行接口和发布的接口共用
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum EndResponse {
+    // 原样返回输出变量
+    Vars(Map<String, Value>),
+    // 文本模式：渲染后的文本
+    Text(String),
+    // 标准格式：R 风格的包装，返回码和消息取自结束节点配置，data 为输出变量或文本
+    Wrapped {
+        code: i32,
+        message: String,
+        data: Value,
+    },
+}
+
+impl EndResponse {
+    // 文本模式优先于输出变量；包装只改变外层结构
+    pub fn new(output: &EndNodeOutput, vars: &Map<String, Value>) -> Self {
+        let text = output
+            .is_text
+            .then(|| render(output.text.as_deref().unwrap_or_default(), vars));
+        if !output.is_wrap {
+            return match text {
+                Some(text) => Self::Text(text),
+                None => Self::Vars(vars.clone()),
+            };
+        }
+
+        let code = output.r_code.unwrap_or(Code::Ok.into());
+        let message = match output.r_message.as_deref().filter(|m| !m.is_empty()) {
+            Some(message) => render(message, vars),
+            None => translate(code, &vec![]),
+        };
+        Self::Wrapped {
+            code,
+            message,
+            data: match text {
+                Some(text) => Value::String(text),
+                None => Value::Object(vars.clone()),
+            },
+        }
+    }
+
+    // 文本模式下的文本，流式输出时逐段发送
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Text(text) => Some(text),
+            Self::Wrapped {
+                data: Value::String(text),
+                ..
+            } => Some(text),
+            _ => None,
+        }
+    }
+}
+
+// 包装的返回码与 R 相同，是合法的 HTTP 状态码时同时作为响应状态
+impl IntoResponse for EndResponse {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Vars(vars) => Json(vars).into_response(),
+            Self::Text(text) => text.into_response(),
+            Self::Wrapped {
+                code,
+                message,
+                data,
+            } => R {
+                code,
+                message,
+                data: Some(data),
+            }
+            .into_response(),
+        }
+    }
+}
+
+// 文本模板：{{name}} 替换为输出变量的值，可用 . 访问对象字段和数组下标，
+// 例如 {{user.name}}、{{items.0}}；字符串原样输出，其他值输出 JSON，变量不存在时为空
+pub fn render(template: &str, vars: &Map<String, Value>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+
+        let path = rest[start + 2..start + 2 + len].trim();
+        let mut parts = path.split('.');
+        let mut value = parts.next().and_then(|name| vars.get(name));
+        for part in parts {
+            value = value.and_then(|v| match v {
+                Value::Object(map) => map.get(part),
+                Value::Array(items) => part.parse::<usize>().ok().and_then(|i| items.get(i)),
+                _ => None,
+            });
+        }
+        match value {
+            Some(Value::String(s)) => out.push_str(s),
+            Some(Value::Null) | None => {}
+            Some(value) => out.push_str(&value.to_string()),
+        }
+        rest = &rest[start + len + 4..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let vars = object(json!({
+            "name": "uvwa", "age": 3, "user": {"tags": ["a", "b"]}, "none": null
+        }));
+        let table = vec![
+            ("hello {{name}}", "hello uvwa"),
+            ("{{ age }} years", "3 years"),
+            ("{{user.tags.1}} / {{user.tags}}", r#"b / ["a","b"]"#),
+            ("[{{none}}][{{missing}}][{{user.x.y}}]", "[][][]"),
+            ("no vars", "no vars"),
+            ("unclosed {{name", "unclosed {{name"),
+        ];
+        for (template, expected) in table {
+            assert_eq!(render(template, &vars), expected, "{}", template);
+        }
+    }
+
+    #[test]
+    fn test_end_response() {
+        let vars = object(json!({"name": "uvwa"}));
+        let output = |value: Value| -> EndNodeOutput { serde_json::from_value(value).unwrap() };
+
+        let response = EndResponse::new(&output(json!({})), &vars);
+        assert_eq!(response, EndResponse::Vars(vars.clone()));
+
+        let response = EndResponse::new(
+            &output(json!({"isText": true, "text": "hi {{name}}"})),
+            &vars,
+        );
+        assert_eq!(response, EndResponse::Text("hi uvwa".to_string()));
+        assert_eq!(response.text(), Some("hi uvwa"));
+
+        let response = EndResponse::new(
+            &output(json!({"isWrap": true, "rCode": "404", "rMessage": "{{name}} not found"})),
+            &vars,
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"code": 404, "message": "uvwa not found", "data": {"name": "uvwa"}})
+        );
+
+        let response = EndResponse::new(
+            &output(
+                json!({"isWrap": true, "rCode": 0, "rMessage": "ok", "isText": true, "text": "{{name}}"}),
+            ),
+            &vars,
+        );
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({"code": 0, "message": "ok", "data": "uvwa"})
+        );
+        assert_eq!(response.text(), Some("uvwa"));
+    }
+}
@@ -0,0 +1,1334 @@
+use crate::engine::condition::select_case;
+use crate::engine::debug::Debugger;
+use crate::engine::error::EngineError;
+use crate::engine::event::{RunEvent, tokens};
+use crate::engine::expr;
+use crate::engine::input::{bind_input, coerce};
+use crate::engine::response::EndResponse;
+use crate::engine::script::{ScriptError, ScriptRequest, executor};
+use crate::engine::sql::{SqlExecutor, SqlTransaction, map_output, prepare};
+use crate::engine::subflow::FlowLoader;
+use crate::engine::trace::{MAX_TRACE_NODES, NodeTrace, node_input, node_output};
+use crate::engine::vars::Scope;
+use crate::models::spec::graph::SpecGraph;
+use crate::models::spec::node::{
+    AssignMode, AssignNode, AssignScope, AssignSource, CodeNode, EndNode, LoopNode, LoopType,
+    NodeKind, SqlNode, SqlTransactionNode, StartNode, SubFlowNode,
+};
+use crate::models::spec::variable::{
+    Variable, conversation_key, loop_index_key, loop_item_key, var_key,
+};
+use crate::models::spec::{AppSpec, Node};
+use serde_json::{Map, Value};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::UnboundedSender;
+
+type BoxFuture<'f, T> = Pin<Box<dyn Future<Output = T> + Send + 'f>>;
+
+// 运行结果：到达的结束节点及其输出
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    pub node_id: String,
+    pub vars: Map<String, Value>,
+    // 按结束节点配置整理的调用响应
+    pub response: EndResponse,
+    pub trace: Vec<NodeTrace>,
+    // 运行结束时会话变量的值，按变量名
+    pub conversation: Map<String, Value>,
+}
+
+// 单次运行的状态
+pub struct RunState {
+    pub scope: Scope,
+    pub trace: Vec<NodeTrace>,
+    // 当前节点的调试日志，节点结束时移入执行记录
+    logs: Vec<String>,
+    input: Map<String, Value>,
+    // 进入事务容器时开启的事务，内层在后
+    transactions: Vec<(String, Box<dyn SqlTransaction>)>,
+}
+
+// 执行限制，启动时由配置初始化
+#[derive(Debug, Clone)]
+pub struct EngineOptions {
+    // 单个循环节点的最大迭代次数，防止死循环占用工作线程
+    pub max_loop_iterations: u64,
+    // 代码节点未配置超时时间时的默认超时（秒）
+    pub script_timeout: u64,
+    // JavaScript 代码节点的内存上限（字节）
+    pub script_memory_limit: usize,
+    // 是否允许执行 Python 代码节点
+    pub python_enabled: bool,
+    // Python 子进程是否在系统级沙箱中运行，见 script::sandbox
+    pub python_sandbox: bool,
+    // python 可执行文件路径
+    pub python: String,
+    // 同时运行的 Python 子进程数量
+    pub python_workers: usize,
+    // Python 子进程的地址空间上限（字节）
+    pub python_memory_limit: usize,
+    // 子流程的最大嵌套层数
+    pub max_subflow_depth: u64,
+    // SQL 节点查询结果的最大行数，超出时节点执行失败
+    pub sql_max_rows: usize,
+}
+
+impl Default for EngineOptions {
+    fn default() -> Self {
+        Self {
+            max_loop_iterations: 10_000,
+            script_timeout: 10,
+            script_memory_limit: 64 * 1024 * 1024,
+            python_enabled: true,
+            python_sandbox: true,
+            python: "python3".to_string(),
+            python_workers: 4,
+            python_memory_limit: 512 * 1024 * 1024,
+            max_subflow_depth: 8,
+            sql_max_rows: 10_000,
+        }
+    }
+}
+
+static OPTIONS: OnceLock<EngineOptions> = OnceLock::new();
+
+impl EngineOptions {
+    pub fn init(options: EngineOptions) {
+        let _ = OPTIONS.set(options);
+    }
+
+    pub fn global() -> EngineOptions {
+        OPTIONS.get().cloned().unwrap_or_default()
+    }
+}
+
+// 节点执行后的去向
+enum Step {
+    // 沿指定出口继续，None 表示默认出口
+    Next(Option<String>),
+    End(RunOutput),
+    Break,
+    Continue,
+}
+
+// 一段链路的执行结果
+enum Flow {
+    Completed,
+    End(RunOutput),
+    // 遇到终止/继续循环节点，交给最近的循环处理
+    Break,
+    Continue,
+}
+
+pub struct Engine<'a> {
+    graph: SpecGraph<'a>,
+    options: EngineOptions,
+    // SQL 节点的执行器，按工作空间解析数据源
+    sql: Option<Arc<dyn SqlExecutor>>,
+    // 子流程加载器，按工作空间查找应用版本
+    flows: Option<Arc<dyn FlowLoader>>,
+    // 调用链上的流程 id，用于检测循环调用，首个为当前运行的应用
+    call_chain: Vec<String>,
+    // 子流程嵌套层数，顶层流程为 0
+    depth: u64,
+    // 会话变量的声明和本轮对话开始时的值
+    conversation_vars: &'a [Variable],
+    conversation: Map<String, Value>,
+    // 流式运行时接收节点进度和文本片段
+    events: Option<UnboundedSender<RunEvent>>,
+    // 调试运行时在节点执行前暂停
+    debugger: Option<Arc<dyn Debugger>>,
+}
+
+impl<'a> Engine<'a> {
+    pub fn new(spec: &'a AppSpec) -> Self {
+        Self::with_options(spec, EngineOptions::global())
+    }
+
+    pub fn with_options(spec: &'a AppSpec, options: EngineOptions) -> Self {
+        Self {
+            graph: SpecGraph::new(spec),
+            options,
+            sql: None,
+            flows: None,
+            call_chain: Vec::new(),
+            depth: 0,
+            conversation_vars: &spec.conversation_variables,
+            conversation: Map::new(),
+            events: None,
+            debugger: None,
+        }
+    }
+
+    pub fn sql(mut self, executor: Arc<dyn SqlExecutor>) -> Self {
+        self.sql = Some(executor);
+        self
+    }
+
+    pub fn flows(mut self, loader: Arc<dyn FlowLoader>) -> Self {
+        self.flows = Some(loader);
+        self
+    }
+
+    // 会话变量的当前值，通常为上一轮对话结束时的值
+    pub fn conversation(mut self, values: Map<String, Value>) -> Self {
+        self.conversation = values;
+        self
+    }
+
+    // 运行事件的接收方，接收方关闭后不再发送
+    pub fn events(mut self, sender: UnboundedSender<RunEvent>) -> Self {
+        self.events = Some(sender);
+        self
+    }
+
+    pub fn debugger(mut self, debugger: Arc<dyn Debugger>) -> Self {
+        self.debugger = Some(debugger);
+        self
+    }
+
+    // 当前运行的应用 id，子流程引用回它时视为循环调用
+    pub fn flow_id(mut self, flow_id: impl Into<String>) -> Self {
+        self.call_chain = vec![flow_id.into()];
+        self
+    }
+
+    pub async fn run(&self, input: Map<String, Value>) -> Result<RunOutput, EngineError> {
+        let (result, trace) = self.run_traced(input).await;
+        result.map(|output| RunOutput { trace, ..output })
+    }
+
+    // 运行并返回各节点的执行记录，运行失败时同样返回出错前的记录
+    pub async fn run_traced(
+        &self,
+        input: Map<String, Value>,
+    ) -> (Result<RunOutput, EngineError>, Vec<NodeTrace>) {
+        let mut state = RunState {
+            scope: Scope::default(),
+            trace: Vec::new(),
+            logs: Vec::new(),
+            input,
+            transactions: Vec::new(),
+        };
+        let result = self.run_state(&mut state).await;
+        (result, state.trace)
+    }
+
+    async fn run_state(&self, state: &mut RunState) -> Result<RunOutput, EngineError> {
+        let start = self.graph.start().ok_or(EngineError::StartMissing)?;
+        self.init_conversation(state)?;
+
+        match self.run_chain(start, state).await? {
+            Flow::End(mut output) => {
+                output.conversation = self
+                    .conversation_vars
+                    .iter()
+                    .map(|v| {
+                        (
+                            v.name.clone(),
+                            state.scope.resolve(Some(&conversation_key(&v.name))),
+                        )
+                    })
+                    .collect();
+                Ok(output)
+            }
+            _ => Err(EngineError::EndNotReached),
+        }
+    }
+
+    // 单独执行代码或 SQL 节点，用于编辑时测试：输入按变量名直接取值，不经过连线和上游节点；
+    // 节点执行失败时记录在返回的执行记录中，代码节点总是收集控制台输出
+    pub async fn run_node(
+        &self,
+        node: &Node,
+        values: &Map<String, Value>,
+    ) -> Result<NodeTrace, EngineError> {
+        let mut node = node.clone();
+        let inputs = match &mut node.kind {
+            NodeKind::Code(code) => {
+                code.debug = true;
+                &mut code.input
+            }
+            NodeKind::Sql(sql) => &mut sql.input,
+            _ => return Err(unsupported(&node)),
+        };
+
+        let mut state = RunState {
+            scope: Scope::default(),
+            trace: Vec::new(),
+            logs: Vec::new(),
+            input: Map::new(),
+            transactions: Vec::new(),
+        };
+        // 输入变量改为引用以变量名为 key 的值
+        for var in inputs.iter_mut() {
+            let value = values.get(&var.name).cloned().unwrap_or(Value::Null);
+            state.scope.set(var.name.clone(), value);
+            var.value = Some(var.name.clone());
+        }
+
+        let mut trace = NodeTrace::new(&node, node_input(&node, &state.scope, &state.input));
+        let started = Instant::now();
+        let result = match &node.kind {
+            NodeKind::Code(code) => self.exec_code(&node, code, &mut state).await,
+            NodeKind::Sql(sql) => self.exec_sql(&node, sql, &mut state).await,
+            _ => Err(unsupported(&node)),
+        };
+        trace.elapsed = started.elapsed().as_millis() as u64;
+        trace.logs = state.logs;
+        match result {
+            Ok(_) => trace.output = node_output(&node, &state.scope),
+            Err(err) => trace.error = Some(err.to_string()),
+        }
+        Ok(trace)
+    }
+
+    // 会话变量按声明的类型转换并校验，没有值时使用声明的默认值
+    fn init_conversation(&self, state: &mut RunState) -> Result<(), EngineError> {
+        let declared = StartNode {
+            input: self.conversation_vars.to_vec(),
+            ..Default::default()
+        };
+        let mut values = bind_input(&declared, &self.conversation).map_err(EngineError::Input)?;
+        for var in self.conversation_vars {
+            let value = match values.remove(&var.name) {
+                Some(Value::Null) | None => match (var.kind, var.value.as_deref()) {
+                    (Some(kind), Some(default)) => {
+                        coerce(kind, Value::from(default)).unwrap_or(Value::Null)
+                    }
+                    (None, Some(default)) => Value::from(default),
+                    (_, None) => Value::Null,
+                },
+                Some(value) => value,
+            };
+            state.scope.set(conversation_key(&var.name), value);
+        }
+        Ok(())
+    }
+
+    // 从 entry 开始沿连线执行；出现分叉时依次执行各分支，任一分支到达结束节点即停止
+    fn run_chain<'s>(
+        &'s self,
+        entry: &'a Node,
+        state: &'s mut RunState,
+    ) -> BoxFuture<'s, Result<Flow, EngineError>> {
+        Box::pin(async move {
+            let mut node = entry;
+            loop {
+                if let Some(debugger) = &self.debugger {
+                    debugger.before_node(node, &state.scope, &state.trace).await;
+                }
+                self.emit(RunEvent::NodeStarted {
+                    node_id: node.id.clone(),
+                    node_type: node.kind.type_name(),
+                });
+                // 先占位再执行，容器节点排在其内部节点之前
+                let traced = (state.trace.len() < MAX_TRACE_NODES).then(|| {
+                    let input = node_input(node, &state.scope, &state.input);
+                    state.trace.push(NodeTrace::new(node, input));
+                    state.trace.len() - 1
+                });
+                let started = Instant::now();
+                let result = self.exec_node(node, state).await;
+                let elapsed = started.elapsed().as_millis() as u64;
+                let logs = std::mem::take(&mut state.logs);
+                if let Some(trace) = traced.and_then(|i| state.trace.get_mut(i)) {
+                    trace.elapsed = elapsed;
+                    trace.logs = logs;
+                    match &result {
+                        Ok(step) => {
+                            trace.output = node_output(node, &state.scope);
+                            // 条件分支节点记录选中的出口
+                            if let (NodeKind::Case(_), Step::Next(Some(handle))) =
+                                (&node.kind, step)
+                            {
+                                trace
+                                    .output
+                                    .insert("branch".to_string(), Value::from(handle.as_str()));
+                            }
+                        }
+                        Err(err) => trace.error = Some(err.to_string()),
+                    }
+                }
+                let step = result?;
+                self.emit(RunEvent::NodeFinished {
+                    node_id: node.id.clone(),
+                    elapsed,
+                });
+
+                let handle = match step {
+                    Step::Next(handle) => handle,
+                    Step::End(output) => return Ok(Flow::End(output)),
+                    Step::Break => return Ok(Flow::Break),
+                    Step::Continue => return Ok(Flow::Continue),
+                };
+
+                let mut next = self.graph.next(&node.id, handle.as_deref());
+                match next.len() {
+                    0 => return Ok(Flow::Completed),
+                    1 => node = next.remove(0),
+                    _ => {
+                        for branch in next {
+                            match self.run_chain(branch, state).await? {
+                                Flow::Completed => {}
+                                flow => return Ok(flow),
+                            }
+                        }
+                        return Ok(Flow::Completed);
+                    }
+                }
+            }
+        })
+    }
+
+    async fn exec_node(&self, node: &'a Node, state: &mut RunState) -> Result<Step, EngineError> {
+        match &node.kind {
+            NodeKind::Start(start) => {
+                exec_start(node, start, state)?;
+                Ok(Step::Next(None))
+            }
+            NodeKind::End(end) => {
+                let output = exec_end(node, end, &state.scope);
+                let stream = end.output.as_ref().is_some_and(|o| o.is_stream);
+                if let Some(text) = output.response.text().filter(|_| stream) {
+                    for token in tokens(text) {
+                        self.emit(RunEvent::Text {
+                            delta: token.to_string(),
+                        });
+                    }
+                }
+                Ok(Step::End(output))
+            }
+            NodeKind::GroupStart | NodeKind::Note(_) => Ok(Step::Next(None)),
+            NodeKind::LoopBreak => Ok(Step::Break),
+            NodeKind::LoopContinue => Ok(Step::Continue),
+            NodeKind::Case(case) => Ok(Step::Next(Some(select_case(case, &state.scope)))),
+            NodeKind::Loop(lp) => self.exec_loop(node, lp, state).await,
+            NodeKind::SqlTransaction(tx) => self.exec_transaction(node, tx, state).await,
+            NodeKind::Code(code) => self.exec_code(node, code, state).await,
+            NodeKind::Sql(sql) => self.exec_sql(node, sql, state).await,
+            NodeKind::SubFlow(sub) => self.exec_subflow(node, sub, state).await,
+            NodeKind::Assign(assign) => {
+                self.exec_assign(node, assign, state)?;
+                Ok(Step::Next(None))
+            }
+        }
+    }
+
+    fn emit(&self, event: RunEvent) {
+        if let Some(events) = &self.events {
+            let _ = events.send(event);
+        }
+    }
+
+    // 循环：每次迭代从容器入口执行一遍循环体
+    // for 遍历数组，while 执行指定次数，forever 直到遇到终止循环节点；
+    // while/forever 的循环项与下标相同
+    async fn exec_loop(
+        &self,
+        node: &'a Node,
+        lp: &LoopNode,
+        state: &mut RunState,
+    ) -> Result<Step, EngineError> {
+        let items: Box<dyn Iterator<Item = Value> + Send> = match lp.loop_type {
+            Some(LoopType::For) => match state.scope.resolve(lp.for_var_id.as_deref()) {
+                Value::Array(items) => Box::new(items.into_iter()),
+                Value::Null => Box::new(std::iter::empty()),
+                _ => {
+                    return Err(EngineError::node_failed(
+                        &node.id,
+                        "loop variable is not an array",
+                    ));
+                }
+            },
+            Some(LoopType::While) => {
+                Box::new((0..lp.while_number.unwrap_or_default()).map(Value::from))
+            }
+            Some(LoopType::Forever) => Box::new((0u64..).map(Value::from)),
+            None => {
+                return Err(EngineError::node_failed(&node.id, "loop type is not set"));
+            }
+        };
+
+        let limit = self.options.max_loop_iterations;
+        let item_key = loop_item_key(&node.id, lp.body_var_name.as_deref());
+        let index_key = loop_index_key(&node.id, lp.body_index_name.as_deref());
+        let entries = self.graph.group_entries(&node.id);
+        'iteration: for (index, item) in items.enumerate() {
+            if index as u64 >= limit {
+                return Err(EngineError::LoopLimit {
+                    node_id: node.id.clone(),
+                    limit,
+                });
+            }
+            // 让出执行权，避免长循环独占工作线程
+            tokio::task::yield_now().await;
+
+            state.scope.set(item_key.clone(), item);
+            state.scope.set(index_key.clone(), Value::from(index));
+            for entry in &entries {
+                match self.run_chain(entry, state).await? {
+                    Flow::Completed => {}
+                    Flow::End(output) => return Ok(Step::End(output)),
+                    Flow::Break => break 'iteration,
+                    Flow::Continue => continue 'iteration,
+                }
+            }
+        }
+        Ok(Step::Next(None))
+    }
+
+    // 代码节点：输入按变量名传给脚本，返回对象中的同名字段按输出变量类型写回变量表
+    async fn exec_code(
+        &self,
+        node: &Node,
+        code: &CodeNode,
+        state: &mut RunState,
+    ) -> Result<Step, EngineError> {
+        let input = code
+            .input
+            .iter()
+            .map(|v| (v.name.clone(), state.scope.resolve(v.value.as_deref())))
+            .collect();
+        let timeout = code
+            .timeout
+            .filter(|t| *t > 0)
+            .unwrap_or(self.options.script_timeout);
+        let request = ScriptRequest {
+            content: code.content.clone(),
+            input,
+            timeout: Duration::from_secs(timeout),
+        };
+
+        let run = executor(code.language, &self.options)
+            .execute(request)
+            .await;
+        if code.debug {
+            state.logs.extend(run.logs);
+        }
+
+        let mut output = run.result.map_err(|err| match err {
+            ScriptError::Timeout => EngineError::ScriptTimeout {
+                node_id: node.id.clone(),
+                timeout,
+            },
+            ScriptError::MemoryLimit => EngineError::ScriptMemory {
+                node_id: node.id.clone(),
+            },
+            ScriptError::Failed(message) => EngineError::node_failed(&node.id, message),
+        })?;
+        for var in &code.output {
+            let value = output.remove(&var.name).unwrap_or(Value::Null);
+            let value = match var.kind {
+                Some(kind) => coerce(kind, value).ok_or_else(|| {
+                    EngineError::node_failed(
+                        &node.id,
+                        format!("output {} is not of type {}", var.name, kind),
+                    )
+                })?,
+                None => value,
+            };
+            state.scope.set(var_key(&node.id, var), value);
+        }
+        Ok(Step::Next(None))
+    }
+
+    // SQL 节点：输入变量按名称绑定为语句参数，结果按输出变量类型写回变量表；
+    // 在事务容器内时使用事务的连接，未指定数据源时使用所在事务的数据源
+    async fn exec_sql(
+        &self,
+        node: &Node,
+        sql: &SqlNode,
+        state: &mut RunState,
+    ) -> Result<Step, EngineError> {
+        let conn_key = sql.conn_key.as_deref().filter(|k| !k.is_empty());
+        let input = sql
+            .input
+            .iter()
+            .map(|v| (v.name.clone(), state.scope.resolve(v.value.as_deref())))
+            .collect();
+        let statement =
+            prepare(&sql.content, &input).map_err(|e| EngineError::node_failed(&node.id, e))?;
+
+        let result = match (conn_key, state.transactions.last_mut()) {
+            (None, None) => Err("datasource is not set".to_string()),
+            (None, Some((_, tx))) => tx.execute(&statement).await,
+            (Some(conn_key), None) => match &self.sql {
+                Some(executor) => executor.execute(conn_key, &statement).await,
+                None => Err("datasource is not available".to_string()),
+            },
+            (Some(conn_key), Some(_)) => {
+                let tx = state
+                    .transactions
+                    .iter_mut()
+                    .rev()
+                    .find(|(key, _)| key == conn_key);
+                match tx {
+                    Some((_, tx)) => tx.execute(&statement).await,
+                    None => Err(format!(
+                        "datasource {} is not the datasource of the transaction",
+                        conn_key
+                    )),
+                }
+            }
+        }
+        .map_err(|e| EngineError::node_failed(&node.id, e))?;
+
+        for var in &sql.output {
+            let value =
+                map_output(var, &result).map_err(|e| EngineError::node_failed(&node.id, e))?;
+            state.scope.set(var_key(&node.id, var), value);
+        }
+        Ok(Step::Next(None))
+    }
+
+    // 事务容器：进入时开启事务，容器内的节点执行完成后提交，任一节点出错则回滚；
+    // 同一数据源的嵌套事务并入外层事务
+    async fn exec_transaction(
+        &self,
+        node: &'a Node,
+        tx: &SqlTransactionNode,
+        state: &mut RunState,
+    ) -> Result<Step, EngineError> {
+        let conn_key = tx
+            .conn_key
+            .as_deref()
+            .filter(|k| !k.is_empty())
+            .ok_or_else(|| EngineError::node_failed(&node.id, "datasource is not set"))?;
+        if state.transactions.iter().any(|(key, _)| key == conn_key) {
+            return self.exec_group(node, state).await;
+        }
+
+        let executor = self
+            .sql
+            .as_ref()
+            .ok_or_else(|| EngineError::node_failed(&node.id, "datasource is not available"))?;
+        let tx = executor
+            .begin(conn_key)
+            .await
+            .map_err(|e| EngineError::node_failed(&node.id, e))?;
+
+        state.transactions.push((conn_key.to_string(), tx));
+        let result = self.exec_group(node, state).await;
+        let Some((_, tx)) = state.transactions.pop() else {
+            return result;
+        };
+        match result {
+            Ok(step) => {
+                tx.commit()
+                    .await
+                    .map_err(|e| EngineError::node_failed(&node.id, e))?;
+                Ok(step)
+            }
+            Err(err) => {
+                if let Err(e) = tx.rollback().await {
+                    tracing::warn!("failed to rollback transaction of node {}: {}", node.id, e);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    // 子流程：加载引用的应用版本，输入按变量名传入，结束节点的同名输出按类型写回变量表；
+    // 子流程使用独立的变量表和事务，调用链中出现重复的流程或超出嵌套层数时报错
+    async fn exec_subflow(
+        &self,
+        node: &'a Node,
+        sub: &SubFlowNode,
+        state: &mut RunState,
+    ) -> Result<Step, EngineError> {
+        let flow_id = sub
+            .flow_id
+            .as_deref()
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| EngineError::node_failed(&node.id, "flow is not set"))?;
+        let loader = self
+            .flows
+            .as_ref()
+            .ok_or_else(|| EngineError::node_failed(&node.id, "subflow is not available"))?;
+        if self.call_chain.iter().any(|id| id == flow_id) {
+            return Err(EngineError::SubflowCycle {
+                node_id: node.id.clone(),
+                flow_id: flow_id.to_string(),
+            });
+        }
+        let limit = self.options.max_subflow_depth;
+        if self.depth >= limit {
+            return Err(EngineError::SubflowDepth {
+                node_id: node.id.clone(),
+                limit,
+            });
+        }
+
+        let spec = loader
+            .load(flow_id, sub.version.as_deref().filter(|v| !v.is_empty()))
+            .await
+            .map_err(|e| EngineError::node_failed(&node.id, e))?;
+        let input = sub
+            .input
+            .iter()
+            .map(|v| (v.name.clone(), state.scope.resolve(v.value.as_deref())))
+            .collect();
+
+        let mut call_chain = self.call_chain.clone();
+        call_chain.push(flow_id.to_string());
+        let child = Engine {
+            graph: SpecGraph::new(&spec),
+            options: self.options.clone(),
+            sql: self.sql.clone(),
+            flows: self.flows.clone(),
+            call_chain,
+            depth: self.depth + 1,
+            conversation_vars: &spec.conversation_variables,
+            conversation: Map::new(),
+            // 子流程内部的节点不单独推送进度，调试时也不进入子流程
+            events: None,
+            debugger: None,
+        };
+        let mut output = child.run(input).await.map_err(|err| match err {
+            EngineError::Input(errors) => EngineError::node_failed(
+                &node.id,
+                format!("invalid input of flow {}: {}", flow_id, errors),
+            ),
+            err => err,
+        })?;
+
+        for var in &sub.output {
+            let value = output.vars.remove(&var.name).unwrap_or(Value::Null);
+            let value = match var.kind {
+                Some(kind) => coerce(kind, value).ok_or_else(|| {
+                    EngineError::node_failed(
+                        &node.id,
+                        format!("output {} is not of type {}", var.name, kind),
+                    )
+                })?,
+                None => value,
+            };
+            state.scope.set(var_key(&node.id, var), value);
+        }
+        Ok(Step::Next(None))
+    }
+
+    // 变量赋值：按顺序执行，后面的赋值项可以读取前面赋值的结果；
+    // 值按目标变量声明的类型转换，无法转换时报错
+    fn exec_assign(
+        &self,
+        node: &Node,
+        assign: &AssignNode,
+        state: &mut RunState,
+    ) -> Result<(), EngineError> {
+        // 流程变量只能赋值给节点处可见的变量，与草稿校验使用同一作用域
+        let visible = self.graph.visible(&node.id);
+        for item in &assign.items {
+            let failed = |message: String| EngineError::node_failed(&node.id, message);
+            let (key, kind) = match item.scope {
+                AssignScope::Workflow => {
+                    let var = visible
+                        .iter()
+                        .find(|v| v.node_id.is_some() && v.var_id == item.target)
+                        .ok_or_else(|| {
+                            failed(format!("variable {} is not available", item.target))
+                        })?;
+                    (item.target.clone(), var.kind)
+                }
+                AssignScope::Conversation => {
+                    let var = self
+                        .conversation_vars
+                        .iter()
+                        .find(|v| v.name == item.target)
+                        .ok_or_else(|| {
+                            failed(format!(
+                                "conversation variable {} is not declared",
+                                item.target
+                            ))
+                        })?;
+                    (conversation_key(&var.name), var.kind)
+                }
+            };
+
+            let value = match &item.source {
+                AssignSource::Literal(value) => value.clone(),
+                AssignSource::Reference(reference) => state.scope.resolve(Some(reference)),
+                AssignSource::Expression(expression) => expr::eval(expression, &state.scope)
+                    .map_err(|e| failed(format!("expression of {}: {}", item.target, e)))?,
+            };
+            let value = match item.mode {
+                AssignMode::Set => value,
+                AssignMode::Append => {
+                    let mut items = match state.scope.resolve(Some(&key)) {
+                        Value::Array(items) if kind.is_none_or(|k| k.is_array()) => items,
+                        Value::Null if kind.is_none_or(|k| k.is_array()) => Vec::new(),
+                        _ => {
+                            return Err(failed(format!(
+                                "variable {} is not an array",
+                                item.target
+                            )));
+                        }
+                    };
+                    match value {
+                        Value::Array(values) => items.extend(values),
+                        value => items.push(value),
+                    }
+                    Value::Array(items)
+                }
+            };
+            let value = match kind {
+                Some(kind) => coerce(kind, value).ok_or_else(|| {
+                    failed(format!("value of {} is not of type {}", item.target, kind))
+                })?,
+                None => value,
+            };
+            state.scope.set(key, value);
+        }
+        Ok(())
+    }
+
+    // 普通容器：执行一遍容器内的节点
+    // 容器内的终止/继续循环节点向外交给所在的循环
+    async fn exec_group(&self, node: &'a Node, state: &mut RunState) -> Result<Step, EngineError> {
+        for entry in self.graph.group_entries(&node.id) {
+            match self.run_chain(entry, state).await? {
+                Flow::Completed => {}
+                Flow::End(output) => return Ok(Step::End(output)),
+                Flow::Break => return Ok(Step::Break),
+                Flow::Continue => return Ok(Step::Continue),
+            }
+        }
+        Ok(Step::Next(None))
+    }
+}
+
+// 单独测试只支持代码和 SQL 节点
+fn unsupported(node: &Node) -> EngineError {
+    EngineError::NodeUnsupported {
+        node_id: node.id.clone(),
+        node_type: node.kind.type_name().to_string(),
+    }
+}
+
+// 开始节点：按变量名从调用参数中取值，转换为声明的类型并校验
+fn exec_start(node: &Node, start: &StartNode, state: &mut RunState) -> Result<(), EngineError> {
+    let mut values = bind_input(start, &state.input).map_err(EngineError::Input)?;
+    for var in &start.input {
+        let value = values.remove(&var.name).unwrap_or(Value::Null);
+        state.scope.set(var_key(&node.id, var), value);
+    }
+    Ok(())
+}
+
+// 结束节点：输出变量的 value 保存引用的上游变量 id，按配置整理为调用响应
+fn exec_end(node: &Node, end: &EndNode, scope: &Scope) -> RunOutput {
+    let output = end.output.clone().unwrap_or_default();
+    let vars = output
+        .vars
+        .iter()
+        .map(|v| (v.name.clone(), scope.resolve(v.value.as_deref())))
+        .collect();
+
+    RunOutput {
+        node_id: node.id.clone(),
+        response: EndResponse::new(&output, &vars),
+        vars,
+        trace: Vec::new(),
+        conversation: Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::sql::{SqlResult, SqlStatement};
+    use serde_json::json;
+
+    async fn run(fixture: &str, input: Value) -> Result<RunOutput, EngineError> {
+        let spec = AppSpec::parse(fixture).unwrap();
+        let Value::Object(input) = input else {
+            panic!("input must be an object");
+        };
+        Engine::new(&spec).run(input).await
+    }
+
+    #[tokio::test]
+    async fn test_run_linear() {
+        let output = run(
+            include_str!("fixtures/linear.json"),
+            json!({"name": "uvwa"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.node_id, "end");
+        assert_eq!(output.vars["greeting"], json!("uvwa"));
+        assert_eq!(output.response.text(), Some("hello uvwa"));
+
+        let err = run(include_str!("fixtures/linear.json"), json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EngineError::Input(errors) if errors.field_errors().contains_key("name"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_events() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [{"id": "n", "name": "name"}]}},
+                {"id": "e", "type": "end", "data": {"output": {
+                    "vars": [{"name": "name", "value": "n"}],
+                    "isText": true, "isStream": true, "text": "hi {{name}}!"}}}
+            ],
+            "edges": [{"id": "1", "source": "s", "target": "e"}]
+        }"#;
+        let spec = AppSpec::parse(spec).unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let Value::Object(input) = json!({"name": "uvwa"}) else {
+            unreachable!()
+        };
+        Engine::new(&spec).events(sender).run(input).await.unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            events.push(match event {
+                RunEvent::NodeStarted { node_id, .. } => format!("+{}", node_id),
+                RunEvent::NodeFinished { node_id, .. } => format!("-{}", node_id),
+                RunEvent::Text { delta } => delta,
+            });
+        }
+        assert_eq!(events, vec!["+s", "-s", "+e", "hi ", "uvwa", "!", "-e"]);
+    }
+
+    #[derive(Default)]
+    struct RecordingDebugger(std::sync::Mutex<Vec<(String, Value, usize)>>);
+
+    #[async_trait::async_trait]
+    impl Debugger for RecordingDebugger {
+        async fn before_node(&self, node: &Node, scope: &Scope, trace: &[NodeTrace]) {
+            let value = scope.resolve(Some("v_name"));
+            let mut calls = self.0.lock().unwrap();
+            calls.push((node.id.clone(), value, trace.len()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_debugger() {
+        let debugger = Arc::new(RecordingDebugger::default());
+        let spec = AppSpec::parse(include_str!("fixtures/linear.json")).unwrap();
+        let Value::Object(input) = json!({"name": "uvwa"}) else {
+            unreachable!()
+        };
+        Engine::new(&spec)
+            .debugger(debugger.clone())
+            .run(input)
+            .await
+            .unwrap();
+
+        // 每个节点执行前调用，此时可以看到上游节点写入的变量和执行记录
+        assert_eq!(
+            *debugger.0.lock().unwrap(),
+            vec![
+                ("start".to_string(), Value::Null, 0),
+                ("end".to_string(), json!("uvwa"), 1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_case() {
+        let fixture = include_str!("fixtures/case.json");
+        let output = run(fixture, json!({"age": 20})).await.unwrap();
+        assert_eq!(output.node_id, "adult");
+
+        let output = run(fixture, json!({"age": 12})).await.unwrap();
+        assert_eq!(output.node_id, "minor");
+        assert_eq!(output.vars["age"], json!(12));
+    }
+
+    #[tokio::test]
+    async fn test_run_loop() {
+        let fixture = include_str!("fixtures/loop.json");
+        let output = run(fixture, json!({"items": ["a", "stop", "b"]}))
+            .await
+            .unwrap();
+        assert_eq!(output.node_id, "found");
+        assert_eq!(output.vars["index"], json!(1));
+
+        let output = run(fixture, json!({"items": ["a", "b"]})).await.unwrap();
+        assert_eq!(output.node_id, "end");
+    }
+
+    #[tokio::test]
+    async fn test_run_loop_control() {
+        let fixture = include_str!("fixtures/loop_control.json");
+        let output = run(fixture, json!({"items": ["skip", "a", "hit"]}))
+            .await
+            .unwrap();
+        assert_eq!(output.node_id, "found");
+        assert_eq!(output.vars["index"], json!(2));
+
+        let output = run(fixture, json!({"items": ["a", "stop", "hit"]}))
+            .await
+            .unwrap();
+        assert_eq!(output.node_id, "end");
+        assert_eq!(output.vars["index"], json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_run_loop_forever() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {}},
+                {"id": "l", "type": "loop", "data": {"type": "forever"}},
+                {"id": "g", "type": "group-start", "parentId": "l", "data": {}},
+                {"id": "c", "type": "case", "parentId": "l", "data": {"cases": [
+                    {"id": "k", "conditions": [{"varId": "l.index", "opr": ">=", "value": "3"}]}]}},
+                {"id": "b", "type": "loop-break", "parentId": "l", "data": {}},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [{"name": "n", "value": "l.index"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "l"},
+                {"id": "2", "source": "g", "target": "c"},
+                {"id": "3", "source": "c", "sourceHandle": "if-k", "target": "b"},
+                {"id": "4", "source": "l", "target": "e"}
+            ]
+        }"#;
+        let output = run(spec, json!({})).await.unwrap();
+        assert_eq!(output.vars["n"], json!(3));
+
+        // 超出迭代上限
+        let spec = AppSpec::parse(&spec.replace(">=", "is empty")).unwrap();
+        let options = EngineOptions {
+            max_loop_iterations: 5,
+            ..Default::default()
+        };
+        let err = Engine::with_options(&spec, options)
+            .run(Map::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::LoopLimit { limit: 5, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_run_code() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [{"id": "n", "name": "n", "type": "INTEGER"}]}},
+                {"id": "c", "type": "code", "data": {
+                    "language": "javascript", "debug": true, "timeout": "1",
+                    "content": "function main({n}) { console.log('n =', n); return {double: n * 2, label: 'x' + n}; }",
+                    "input": [{"name": "n", "value": "n"}],
+                    "output": [{"id": "d", "name": "double", "type": "STRING"}, {"id": "l", "name": "label", "type": "STRING"}]
+                }},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [
+                    {"name": "double", "value": "d"}, {"name": "label", "value": "l"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "c"},
+                {"id": "2", "source": "c", "target": "e"}
+            ]
+        }"#;
+        let output = run(spec, json!({"n": 21})).await.unwrap();
+        assert_eq!(output.vars["double"], json!("42"));
+        assert_eq!(output.vars["label"], json!("x21"));
+        let nodes: Vec<_> = output.trace.iter().map(|t| t.node_id.as_str()).collect();
+        assert_eq!(nodes, vec!["s", "c", "e"]);
+        let code = &output.trace[1];
+        assert_eq!(code.logs, vec!["n = 21"]);
+        assert_eq!(Value::Object(code.input.clone()), json!({"n": 21}));
+        assert_eq!(
+            Value::Object(code.output.clone()),
+            json!({"double": "42", "label": "x21"})
+        );
+        assert!(output.trace[0].logs.is_empty());
+
+        // 运行失败时保留出错前的记录，出错的节点带有错误信息
+        let spec = AppSpec::parse(&spec.replace("return {", "while (true) {} return {")).unwrap();
+        let mut input = Map::new();
+        input.insert("n".to_string(), json!(1));
+        let (result, trace) = Engine::new(&spec).run_traced(input).await;
+        assert!(matches!(result, Err(EngineError::ScriptTimeout { .. })));
+        assert_eq!(trace.len(), 2);
+        assert!(trace[0].error.is_none());
+        assert!(
+            trace[1]
+                .error
+                .as_deref()
+                .is_some_and(|e| e.contains("timed out"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_node() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "c", "type": "code", "data": {
+                    "language": "javascript",
+                    "content": "function main({n}) { console.log('n =', n); return {double: n * 2}; }",
+                    "input": [{"name": "n", "value": "upstream"}],
+                    "output": [{"id": "d", "name": "double", "type": "INTEGER"}]
+                }},
+                {"id": "e", "type": "end", "data": {}}
+            ]
+        }"#;
+        let spec = AppSpec::parse(spec).unwrap();
+        let engine = Engine::new(&spec);
+        let Value::Object(values) = json!({"n": 21}) else {
+            unreachable!()
+        };
+
+        // 输入按变量名取值，未开启调试模式同样收集日志
+        let trace = engine.run_node(&spec.nodes[0], &values).await.unwrap();
+        assert_eq!(Value::Object(trace.input), json!({"n": 21}));
+        assert_eq!(Value::Object(trace.output), json!({"double": 42}));
+        assert_eq!(trace.logs, vec!["n = 21"]);
+        assert!(trace.error.is_none());
+
+        // 节点执行失败记录在执行记录中
+        let mut node = spec.nodes[0].clone();
+        if let NodeKind::Code(code) = &mut node.kind {
+            code.content = "function main() { throw new Error('boom'); }".to_string();
+        }
+        let trace = engine.run_node(&node, &values).await.unwrap();
+        assert!(trace.error.is_some_and(|e| e.contains("boom")));
+
+        let err = engine.run_node(&spec.nodes[1], &values).await.unwrap_err();
+        assert!(matches!(err, EngineError::NodeUnsupported { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_run_assign() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [
+                    {"id": "n", "name": "n", "type": "INTEGER"},
+                    {"id": "tags", "name": "tags", "type": "ARRAY_STRING"}]}},
+                {"id": "a", "type": "assign", "data": {"items": [
+                    {"target": "n", "source": {"type": "expression", "value": "{{n}} * 2"}},
+                    {"target": "tags", "mode": "append", "source": {"type": "literal", "value": "x"}},
+                    {"target": "tags", "mode": "append", "source": {"type": "reference", "value": "n"}},
+                    {"scope": "conversation", "target": "turns",
+                     "source": {"type": "expression", "value": "{{conversation.turns}} + 1"}},
+                    {"scope": "conversation", "target": "history", "mode": "append",
+                     "source": {"type": "expression", "value": "'n=' + {{n}}"}}
+                ]}},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [
+                    {"name": "n", "value": "n"}, {"name": "tags", "value": "tags"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "a"},
+                {"id": "2", "source": "a", "target": "e"}
+            ],
+            "conversationVariables": [
+                {"name": "turns", "type": "INTEGER", "value": "0"},
+                {"name": "history", "type": "ARRAY_STRING"}
+            ]
+        }"#;
+        let parsed = AppSpec::parse(spec).unwrap();
+        let Value::Object(input) = json!({"n": 21, "tags": ["a"]}) else {
+            unreachable!()
+        };
+        let Value::Object(conversation) = json!({"turns": "2"}) else {
+            unreachable!()
+        };
+        let output = Engine::new(&parsed)
+            .conversation(conversation)
+            .run(input.clone())
+            .await
+            .unwrap();
+        assert_eq!(output.vars["n"], json!(42));
+        assert_eq!(output.vars["tags"], json!(["a", "x", "42"]));
+        assert_eq!(
+            Value::Object(output.conversation),
+            json!({"turns": 3, "history": ["n=42"]})
+        );
+
+        // 未传入会话变量时使用声明的默认值
+        let output = Engine::new(&parsed).run(input).await.unwrap();
+        assert_eq!(output.conversation["turns"], json!(1));
+
+        // 值不符合目标变量的类型、对非数组追加、目标未声明或在赋值节点处不可见
+        let table = vec![
+            r#"{"target": "n", "source": {"type": "literal", "value": "abc"}}"#,
+            r#"{"target": "n", "mode": "append", "source": {"type": "literal", "value": 1}}"#,
+            r#"{"target": "missing", "source": {"type": "literal", "value": 1}}"#,
+            r#"{"target": "later", "source": {"type": "literal", "value": 1}}"#,
+            r#"{"scope": "conversation", "target": "n", "source": {"type": "literal", "value": 1}}"#,
+            r#"{"target": "n", "source": {"type": "expression", "value": "{{n}} +"}}"#,
+        ];
+        for item in table {
+            let spec = format!(
+                r#"{{
+                    "nodes": [
+                        {{"id": "s", "type": "start", "data": {{"input": [{{"id": "n", "name": "n", "type": "INTEGER"}}]}}}},
+                        {{"id": "a", "type": "assign", "data": {{"items": [{}]}}}},
+                        {{"id": "e", "type": "end", "data": {{}}}},
+                        {{"id": "d", "type": "code", "data": {{"output": [{{"id": "later", "name": "later"}}]}}}}
+                    ],
+                    "edges": [
+                        {{"id": "1", "source": "s", "target": "a"}},
+                        {{"id": "2", "source": "a", "target": "e"}}
+                    ]
+                }}"#,
+                item
+            );
+            let err = run(&spec, json!({"n": 1})).await.unwrap_err();
+            assert!(
+                matches!(&err, EngineError::NodeFailed { node_id, .. } if node_id == "a"),
+                "{}",
+                item
+            );
+        }
+    }
+
+    #[derive(Default)]
+    struct MockSql(std::sync::Mutex<Vec<(String, SqlStatement)>>);
+
+    #[async_trait::async_trait]
+    impl SqlExecutor for MockSql {
+        async fn execute(
+            &self,
+            conn_key: &str,
+            statement: &SqlStatement,
+        ) -> Result<SqlResult, String> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((conn_key.to_string(), statement.clone()));
+            let Value::Object(row) = json!({"id": 7, "name": "uvwa"}) else {
+                unreachable!()
+            };
+            Ok(SqlResult {
+                rows: vec![row],
+                affected: 1,
+            })
+        }
+
+        async fn begin(&self, _conn_key: &str) -> Result<Box<dyn SqlTransaction>, String> {
+            Err("transaction is not supported".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_sql() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [{"id": "name", "name": "name", "type": "STRING"}]}},
+                {"id": "q", "type": "sql", "data": {
+                    "connKey": "main",
+                    "content": "SELECT id, name FROM user WHERE name = #{name}",
+                    "input": [{"name": "name", "value": "name"}],
+                    "output": [{"id": "rows", "name": "rows", "type": "ARRAY_OBJECT"}, {"id": "rowNum", "name": "rowNum", "type": "INTEGER"}]
+                }},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [
+                    {"name": "rows", "value": "rows"}, {"name": "count", "value": "rowNum"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "q"},
+                {"id": "2", "source": "q", "target": "e"}
+            ]
+        }"#;
+        let spec = AppSpec::parse(spec).unwrap();
+        let sql = Arc::new(MockSql::default());
+        let Value::Object(input) = json!({"name": "uvwa' OR 1=1"}) else {
+            unreachable!()
+        };
+        let output = Engine::new(&spec)
+            .sql(sql.clone())
+            .run(input.clone())
+            .await
+            .unwrap();
+        assert_eq!(output.vars["rows"], json!([{"id": 7, "name": "uvwa"}]));
+        assert_eq!(output.vars["count"], json!(1));
+
+        // 输入作为参数绑定，不拼接到语句中
+        let (conn_key, statement) = sql.0.lock().unwrap().remove(0);
+        assert_eq!(conn_key, "main");
+        assert_eq!(statement.sql, "SELECT id, name FROM user WHERE name = ?");
+        assert_eq!(statement.params, vec![json!("uvwa' OR 1=1")]);
+
+        let err = Engine::new(&spec).run(input).await.unwrap_err();
+        assert!(matches!(err, EngineError::NodeFailed { node_id, .. } if node_id == "q"));
+    }
+
+    // 按 id 返回固定的流程
+    struct MockFlows;
+
+    #[async_trait::async_trait]
+    impl FlowLoader for MockFlows {
+        async fn load(&self, flow_id: &str, _version: Option<&str>) -> Result<AppSpec, String> {
+            let spec = match flow_id {
+                "echo" => r#"{
+                    "nodes": [
+                        {"id": "s", "type": "start", "data": {"input": [{"id": "n", "name": "n", "type": "INTEGER"}]}},
+                        {"id": "e", "type": "end", "data": {"output": {"vars": [{"name": "result", "value": "n"}]}}}
+                    ],
+                    "edges": [{"id": "1", "source": "s", "target": "e"}]
+                }"#
+                .to_string(),
+                "missing" => return Err("flow missing is not found".to_string()),
+                other => subflow_spec(&other.replace("call-", "")),
+            };
+            AppSpec::parse(&spec).map_err(|_| "invalid spec".to_string())
+        }
+    }
+
+    // 调用指定流程的流程：call-echo → echo，call-call-echo → call-echo → echo
+    fn subflow_spec(flow_id: &str) -> String {
+        format!(
+            r#"{{
+                "nodes": [
+                    {{"id": "s", "type": "start", "data": {{"input": [{{"id": "n", "name": "n", "type": "INTEGER"}}]}}}},
+                    {{"id": "x", "type": "subflow", "data": {{
+                        "flowId": "{}",
+                        "input": [{{"name": "n", "value": "n"}}],
+                        "output": [{{"id": "r", "name": "result", "type": "STRING"}}]
+                    }}}},
+                    {{"id": "e", "type": "end", "data": {{"output": {{"vars": [{{"name": "result", "value": "r"}}]}}}}}}
+                ],
+                "edges": [
+                    {{"id": "1", "source": "s", "target": "x"}},
+                    {{"id": "2", "source": "x", "target": "e"}}
+                ]
+            }}"#,
+            flow_id
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_subflow() {
+        let run = |flow_id: &str, options: EngineOptions| {
+            let spec = AppSpec::parse(&subflow_spec(flow_id)).unwrap();
+            async move {
+                let Value::Object(input) = json!({"n": 21}) else {
+                    unreachable!()
+                };
+                Engine::with_options(&spec, options)
+                    .flows(Arc::new(MockFlows))
+                    .flow_id("main")
+                    .run(input)
+                    .await
+            }
+        };
+
+        // 输入按变量名传入，子流程的输出按类型写回
+        let output = run("call-echo", EngineOptions::default()).await.unwrap();
+        assert_eq!(output.vars["result"], json!("21"));
+
+        let err = run("call-main", EngineOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EngineError::SubflowCycle { flow_id, .. } if flow_id == "main"));
+
+        let options = EngineOptions {
+            max_subflow_depth: 1,
+            ..Default::default()
+        };
+        let err = run("call-echo", options).await.unwrap_err();
+        assert!(matches!(err, EngineError::SubflowDepth { limit: 1, .. }));
+
+        let err = run("missing", EngineOptions::default()).await.unwrap_err();
+        assert!(matches!(err, EngineError::NodeFailed { node_id, .. } if node_id == "x"));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::engine::script::{LogBuffer, ScriptError, ScriptExecutor, ScriptRequest, ScriptRun};
+use async_trait::async_trait;
+use rquickjs::function::Rest;
+use rquickjs::{CaughtError, Context, Ctx, Function, Object, Runtime};
+use serde_json::{Map, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// 内嵌 QuickJS 执行 JavaScript
+// 只提供语言内置对象和 console，不加载 std/os 模块，脚本无法访问文件和网络
+pub struct JsExecutor {
+    // 运行时内存上限（字节）
+    memory_limit: usize,
+}
+
+impl JsExecutor {
+    pub fn new(memory_limit: usize) -> Self {
+        Self { memory_limit }
+    }
+}
+
+#[async_trait]
+impl ScriptExecutor for JsExecutor {
+    async fn execute(&self, request: ScriptRequest) -> ScriptRun {
+        let memory_limit = self.memory_limit;
+        tokio::task::spawn_blocking(move || run(request, memory_limit))
+            .await
+            .unwrap_or_else(|e| ScriptRun {
+                result: Err(ScriptError::Failed(e.to_string())),
+                logs: vec![],
+            })
+    }
+}
+
+fn run(request: ScriptRequest, memory_limit: usize) -> ScriptRun {
+    let logs = Arc::new(Mutex::new(LogBuffer::default()));
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let result = eval_in_runtime(&request, memory_limit, logs.clone(), timed_out.clone());
+
+    let result = match result {
+        Ok(output) => Ok(output),
+        Err(_) if timed_out.load(Ordering::Relaxed) => Err(ScriptError::Timeout),
+        Err(message) if message.contains("out of memory") => Err(ScriptError::MemoryLimit),
+        Err(message) => Err(ScriptError::Failed(message)),
+    };
+    let logs = logs
+        .lock()
+        .map(|mut l| std::mem::take(&mut *l).into_logs())
+        .unwrap_or_default();
+    ScriptRun { result, logs }
+}
+
+fn eval_in_runtime(
+    request: &ScriptRequest,
+    memory_limit: usize,
+    logs: Arc<Mutex<LogBuffer>>,
+    timed_out: Arc<AtomicBool>,
+) -> Result<Map<String, Value>, String> {
+    let runtime = Runtime::new().map_err(|e| e.to_string())?;
+    runtime.set_memory_limit(memory_limit);
+
+    // 引擎执行期间会周期性调用中断检查，超过截止时间即终止脚本
+    let deadline = Instant::now() + request.timeout;
+    runtime.set_interrupt_handler(Some(Box::new(move || {
+        let expired = Instant::now() >= deadline;
+        if expired {
+            timed_out.store(true, Ordering::Relaxed);
+        }
+        expired
+    })));
+
+    let context = Context::full(&runtime).map_err(|e| e.to_string())?;
+    context.with(|ctx| {
+        let value = eval(&ctx, request, logs).map_err(|e| {
+            CaughtError::from_error(&ctx, e)
+                .to_string()
+                .trim()
+                .to_string()
+        })?;
+        match value {
+            Value::Object(output) => Ok(output),
+            Value::Null => Ok(Map::new()),
+            _ => Err("script must return an object".to_string()),
+        }
+    })
+}
+
+// 输入变量既作为同名全局变量，也通过 input 对象传入；
+// 脚本定义了 main 函数时返回 main(input) 的结果，否则返回脚本最后一个表达式的值
+fn eval<'js>(
+    ctx: &Ctx<'js>,
+    request: &ScriptRequest,
+    logs: Arc<Mutex<LogBuffer>>,
+) -> rquickjs::Result<Value> {
+    let globals = ctx.globals();
+    globals.set("console", console(ctx, logs)?)?;
+
+    let input_json = serde_json::to_string(&request.input).unwrap_or_else(|_| "{}".to_string());
+    let input = ctx.json_parse(input_json)?;
+    if let Some(object) = input.as_object() {
+        for name in request.input.keys().filter(|n| !n.is_empty()) {
+            globals.set(
+                name.as_str(),
+                object.get::<_, rquickjs::Value>(name.as_str())?,
+            )?;
+        }
+    }
+    globals.set("input", input.clone())?;
+
+    let mut result: rquickjs::Value = ctx.eval(request.content.as_str())?;
+    let main: rquickjs::Value = globals.get("main")?;
+    if let Some(main) = main.as_function() {
+        result = main.call((input,))?;
+    }
+    if let Some(promise) = result.as_promise() {
+        result = promise.finish()?;
+    }
+
+    let json = match ctx.json_stringify(result)? {
+        Some(json) => json.to_string()?,
+        None => return Ok(Value::Null),
+    };
+    Ok(serde_json::from_str(&json).unwrap_or(Value::Null))
+}
+
+// console.log/info/warn/error/debug 写入执行日志，超出上限后不再格式化参数
+fn console<'js>(ctx: &Ctx<'js>, logs: Arc<Mutex<LogBuffer>>) -> rquickjs::Result<Object<'js>> {
+    let console = Object::new(ctx.clone())?;
+    for level in ["log", "info", "warn", "error", "debug"] {
+        let logs = logs.clone();
+        let func = Function::new(
+            ctx.clone(),
+            move |ctx: Ctx<'js>, args: Rest<rquickjs::Value<'js>>| -> rquickjs::Result<()> {
+                if logs.lock().is_ok_and(|l| l.is_full()) {
+                    return Ok(());
+                }
+                let mut parts = Vec::with_capacity(args.0.len());
+                for arg in args.0 {
+                    let text = match arg.as_string() {
+                        Some(s) => s.to_string()?,
+                        None => match ctx.json_stringify(arg)? {
+                            Some(s) => s.to_string()?,
+                            None => "undefined".to_string(),
+                        },
+                    };
+                    parts.push(text);
+                }
+                let line = match level {
+                    "log" => parts.join(" "),
+                    level => format!("[{}] {}", level, parts.join(" ")),
+                };
+                if let Ok(mut logs) = logs.lock() {
+                    logs.push(line);
+                }
+                Ok(())
+            },
+        )?;
+        console.set(level, func)?;
+    }
+    Ok(console)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::script::{LOGS_TRUNCATED, MAX_LOG_BYTES, MAX_LOG_LINES};
+    use serde_json::json;
+    use std::time::Duration;
+
+    async fn execute(content: &str, input: Value) -> ScriptRun {
+        let Value::Object(input) = input else {
+            panic!("input must be an object");
+        };
+        let request = ScriptRequest {
+            content: content.to_string(),
+            input,
+            timeout: Duration::from_millis(500),
+        };
+        JsExecutor::new(16 * 1024 * 1024).execute(request).await
+    }
+
+    #[tokio::test]
+    async fn test_js_execute() {
+        let run = execute(
+            "function main(input) { console.log('sum', a + b, {x: 1}); return {sum: input.a + input.b}; }",
+            json!({"a": 1, "b": 2}),
+        )
+        .await;
+        assert_eq!(run.result.unwrap()["sum"], json!(3));
+        assert_eq!(run.logs, vec![r#"sum 3 {"x":1}"#]);
+
+        // 没有 main 函数时取最后一个表达式的值
+        let run = execute("({upper: name.toUpperCase()})", json!({"name": "uvwa"})).await;
+        assert_eq!(run.result.unwrap()["upper"], json!("UVWA"));
+
+        let run = execute("async function main() { return {ok: true}; }", json!({})).await;
+        assert_eq!(run.result.unwrap()["ok"], json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_js_errors() {
+        let run = execute("console.warn('oops'); throw new Error('boom')", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Failed(m)) if m.contains("boom")));
+        assert_eq!(run.logs, vec!["[warn] oops"]);
+
+        let run = execute("while (true) {}", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Timeout)));
+
+        let run = execute(
+            "let a = []; while (true) { a.push('x'.repeat(1024)); }",
+            json!({}),
+        )
+        .await;
+        assert!(matches!(run.result, Err(ScriptError::MemoryLimit)));
+
+        // 没有 require、文件和网络相关的全局对象
+        let run = execute(
+            "({types: [typeof require, typeof std, typeof os, typeof fetch, typeof XMLHttpRequest]})",
+            json!({}),
+        )
+        .await;
+        assert_eq!(run.result.unwrap()["types"], json!(vec!["undefined"; 5]));
+
+        let run = execute("1 + 1", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Failed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_js_log_limit() {
+        let run = execute(
+            "for (let i = 0; i < 5000; i++) console.log(i); ({})",
+            json!({}),
+        )
+        .await;
+        assert!(run.result.is_ok());
+        assert_eq!(run.logs.len(), MAX_LOG_LINES + 1);
+        assert_eq!(run.logs.last().map(String::as_str), Some(LOGS_TRUNCATED));
+
+        // 无限输出大日志时只保留上限以内的部分，直到超时
+        let run = execute("for (;;) console.log('x'.repeat(1e5))", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Timeout)));
+        let bytes: usize = run.logs.iter().map(String::len).sum();
+        assert!(bytes <= MAX_LOG_BYTES + LOGS_TRUNCATED.len());
+        assert_eq!(run.logs.last().map(String::as_str), Some(LOGS_TRUNCATED));
+    }
+}
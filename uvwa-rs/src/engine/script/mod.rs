@@ -0,0 +1,127 @@
+pub mod js;
+pub mod python;
+#[cfg(target_os = "linux")]
+pub mod sandbox;
+
+use crate::engine::runner::EngineOptions;
+use crate::engine::script::js::JsExecutor;
+use crate::engine::script::python::PythonExecutor;
+use crate::models::spec::node::ScriptLanguage;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+
+// 代码节点的一次执行请求：输入按变量名绑定，脚本返回对象映射到输出变量
+#[derive(Debug, Clone)]
+pub struct ScriptRequest {
+    pub content: String,
+    pub input: Map<String, Value>,
+    pub timeout: Duration,
+}
+
+// 执行结果，失败时同样保留已输出的日志供调试
+#[derive(Debug)]
+pub struct ScriptRun {
+    pub result: Result<Map<String, Value>, ScriptError>,
+    pub logs: Vec<String>,
+}
+
+// 执行日志的上限，日志保存在宿主内存中，不受脚本内存限制的约束
+pub const MAX_LOG_LINES: usize = 1000;
+pub const MAX_LOG_BYTES: usize = 1024 * 1024;
+pub const LOGS_TRUNCATED: &str = "... logs truncated";
+
+// 收集执行日志，超出行数或字节数上限后丢弃后续日志，并在末尾标记
+#[derive(Debug, Default)]
+pub struct LogBuffer {
+    lines: Vec<String>,
+    bytes: usize,
+    truncated: bool,
+}
+
+impl LogBuffer {
+    pub fn is_full(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.truncated {
+            return;
+        }
+        if self.lines.len() >= MAX_LOG_LINES || self.bytes + line.len() > MAX_LOG_BYTES {
+            self.truncated = true;
+            return;
+        }
+        self.bytes += line.len();
+        self.lines.push(line);
+    }
+
+    // 日志在进入缓冲前已被截断
+    pub fn set_truncated(&mut self) {
+        self.truncated = true;
+    }
+
+    pub fn into_logs(mut self) -> Vec<String> {
+        if self.truncated {
+            self.lines.push(LOGS_TRUNCATED.to_string());
+        }
+        self.lines
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ScriptError {
+    #[error("script timed out")]
+    Timeout,
+    #[error("script exceeded the memory limit")]
+    MemoryLimit,
+    #[error("{0}")]
+    Failed(String),
+}
+
+// 脚本执行器，每种语言一个实现
+#[async_trait]
+pub trait ScriptExecutor: Send + Sync {
+    async fn execute(&self, request: ScriptRequest) -> ScriptRun;
+}
+
+// Python 子进程工作池，所有运行共享
+static PYTHON_POOL: OnceLock<Arc<Semaphore>> = OnceLock::new();
+
+// 按脚本语言选择执行器
+pub fn executor(language: ScriptLanguage, options: &EngineOptions) -> Box<dyn ScriptExecutor> {
+    match language {
+        ScriptLanguage::Javascript => Box::new(JsExecutor::new(options.script_memory_limit)),
+        ScriptLanguage::Python if !options.python_enabled => Box::new(DisabledExecutor),
+        ScriptLanguage::Python => {
+            let pool = PYTHON_POOL
+                .get_or_init(|| Arc::new(Semaphore::new(options.python_workers.max(1))))
+                .clone();
+            Box::new(PythonExecutor::new(
+                options.python.clone(),
+                options.python_memory_limit,
+                options.python_sandbox,
+                pool,
+            ))
+        }
+    }
+}
+
+// 未开启的语言，执行时直接返回错误
+struct DisabledExecutor;
+
+#[async_trait]
+impl ScriptExecutor for DisabledExecutor {
+    async fn execute(&self, _request: ScriptRequest) -> ScriptRun {
+        ScriptRun {
+            result: Err(ScriptError::Failed(
+                "python code nodes are disabled, set engine.python_enabled to enable them"
+                    .to_string(),
+            )),
+            logs: vec![],
+        }
+    }
+}
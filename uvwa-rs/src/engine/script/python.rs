@@ -0,0 +1,432 @@
+#[cfg(target_os = "linux")]
+use crate::engine::script::sandbox::{self, Sandbox};
+use crate::engine::script::{
+    LogBuffer, MAX_LOG_BYTES, ScriptError, ScriptExecutor, ScriptRequest, ScriptRun,
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::pin::pin;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+// 子进程中执行的引导脚本：
+// 1. 从 stdin 读取 {content, input}，结果写入原 stdout，用户代码的 print 重定向到 stderr
+// 2. 通过审计钩子禁止写文件、读取标准库以外的文件、网络和创建子进程
+// 3. 定义了 main 函数时返回 main(input) 的结果，否则取全局变量 output
+//
+// 审计钩子只是黑名单，_posixsubprocess.fork_exec 等 C 层入口可以绕过，只用于给出清晰的错误。
+// 隔离由 sandbox 提供：子进程在独立的用户、网络命名空间中运行，Landlock 只允许读取
+// python 安装目录和系统库目录，并且不能写入任何文件（engine.python_sandbox，默认开启）
+const BOOTSTRAP: &str = r#"
+import json, os, sys
+
+def _run():
+    request = json.loads(sys.stdin.read())
+    out = os.dup(1)
+    os.dup2(2, 1)
+    sys.stdout = sys.stderr
+
+    allowed = tuple({p for p in (sys.prefix, sys.base_prefix, sys.exec_prefix, sys.base_exec_prefix) if p})
+    blocked = ("socket.", "subprocess.", "os.system", "os.exec", "os.posix_spawn", "os.spawn",
+               "os.fork", "os.forkpty", "os.kill", "os.remove", "os.rename", "os.rmdir", "os.mkdir",
+               "os.chmod", "os.chown", "os.truncate", "os.link", "os.symlink", "os.listdir",
+               "os.scandir", "os.chdir", "os.putenv", "os.unsetenv", "shutil.", "ctypes.",
+               "urllib.", "http.", "ftplib.", "smtplib.", "webbrowser.", "sqlite3.")
+
+    def audit(event, args):
+        if event == "open":
+            path, mode, flags = args
+            writing = (isinstance(mode, str) and any(c in mode for c in "wax+")) \
+                or (isinstance(flags, int) and flags & (os.O_WRONLY | os.O_RDWR | os.O_CREAT))
+            if writing or not isinstance(path, str) or not os.path.abspath(path).startswith(allowed):
+                raise PermissionError("file access is not allowed")
+        elif event.startswith(blocked):
+            raise PermissionError(event + " is not allowed")
+
+    sys.addaudithook(audit)
+    data = request["input"]
+    namespace = {"__name__": "__main__"}
+    namespace.update(data)
+    namespace["input"] = data
+    try:
+        exec(compile(request["content"], "<code>", "exec"), namespace)
+        main = namespace.get("main")
+        output = main(data) if callable(main) else namespace.get("output")
+        result = {"ok": True, "output": output}
+    except MemoryError:
+        result = {"ok": False, "memory": True}
+    except BaseException as e:
+        result = {"ok": False, "error": "%s: %s" % (type(e).__name__, e)}
+    os.write(out, json.dumps(result, default=str).encode())
+
+_run()
+"#;
+
+// 结果 JSON 的大小上限，print 的输出（stderr）按日志上限限制；
+// RLIMIT_FSIZE 对管道无效，超出时结束子进程
+const MAX_RESULT_BYTES: usize = 16 * 1024 * 1024;
+
+// 子进程的输出，exceeded 表示某个管道超出上限后被结束
+struct WorkerOutput {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    exceeded: bool,
+}
+
+// Python 执行器：每次执行启动一个受资源限制的子进程，通过 stdin/stdout 交换 JSON
+// 同时运行的子进程数量由共享的工作池限制
+pub struct PythonExecutor {
+    // python 可执行文件路径
+    python: String,
+    // 子进程地址空间上限（字节）
+    memory_limit: usize,
+    // 是否在系统级沙箱中运行子进程
+    sandbox: bool,
+    pool: Arc<Semaphore>,
+}
+
+#[derive(Deserialize)]
+struct WorkerResult {
+    ok: bool,
+    output: Option<Value>,
+    error: Option<String>,
+    #[serde(default)]
+    memory: bool,
+}
+
+impl PythonExecutor {
+    pub fn new(python: String, memory_limit: usize, sandbox: bool, pool: Arc<Semaphore>) -> Self {
+        Self {
+            python,
+            memory_limit,
+            sandbox,
+            pool,
+        }
+    }
+
+    fn command(&self, request: &ScriptRequest) -> Result<Command, ScriptError> {
+        // -I 隔离模式：忽略 PYTHON* 环境变量和用户目录；-B 不写字节码文件
+        let mut cmd = Command::new(&self.python);
+        cmd.args(["-I", "-B", "-c", BOOTSTRAP])
+            .env_clear()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        #[cfg(target_os = "linux")]
+        let sandbox = self.sandbox.then(|| {
+            let python = sandbox::install_dir(&self.python);
+            Sandbox::new(&python.into_iter().collect::<Vec<_>>())
+        });
+        #[cfg(not(target_os = "linux"))]
+        if self.sandbox {
+            return Err(ScriptError::Failed(
+                "python sandbox is only supported on linux".to_string(),
+            ));
+        }
+
+        #[cfg(unix)]
+        {
+            let memory = self.memory_limit as u64;
+            let cpu = request.timeout.as_secs() + 1;
+            // SAFETY: pre_exec 中只调用 async-signal-safe 的 setrlimit 和沙箱的系统调用
+            unsafe {
+                cmd.pre_exec(move || {
+                    let limits = [
+                        (libc::RLIMIT_AS, memory),
+                        (libc::RLIMIT_CPU, cpu),
+                        (libc::RLIMIT_FSIZE, 0),
+                        (libc::RLIMIT_NOFILE, 64),
+                    ];
+                    for (resource, value) in limits {
+                        let limit = libc::rlimit {
+                            rlim_cur: value as libc::rlim_t,
+                            rlim_max: value as libc::rlim_t,
+                        };
+                        if libc::setrlimit(resource, &limit) != 0 {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                    }
+                    #[cfg(target_os = "linux")]
+                    if let Some(sandbox) = &sandbox {
+                        sandbox.restrict()?;
+                    }
+                    Ok(())
+                });
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = request;
+        Ok(cmd)
+    }
+
+    async fn spawn(&self, request: &ScriptRequest) -> Result<WorkerOutput, ScriptError> {
+        let mut child = self.command(request)?.spawn().map_err(|e| {
+            let hint = match self.sandbox {
+                true => " (the sandbox requires user namespaces and landlock)",
+                false => "",
+            };
+            ScriptError::Failed(format!("failed to start python: {}{}", e, hint))
+        })?;
+
+        let payload = json!({"content": request.content, "input": request.input}).to_string();
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(payload.as_bytes())
+                .await
+                .map_err(|e| ScriptError::Failed(e.to_string()))?;
+        }
+
+        // 同时读取两个管道，任一超出上限立即结束子进程，另一个管道随之关闭；
+        // 超时后丢弃 future，kill_on_drop 会结束子进程
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let collect = async {
+            let mut out = pin!(read_capped(stdout, MAX_RESULT_BYTES));
+            let mut err = pin!(read_capped(stderr, MAX_LOG_BYTES));
+            let (out, err) = tokio::select! {
+                out = &mut out => {
+                    if out.1 {
+                        let _ = child.start_kill();
+                    }
+                    (out, err.await)
+                }
+                err = &mut err => {
+                    if err.1 {
+                        let _ = child.start_kill();
+                    }
+                    (out.await, err)
+                }
+            };
+            let exceeded = out.1 || err.1;
+            if exceeded {
+                let _ = child.start_kill();
+            }
+            child.wait().await?;
+            Ok::<_, std::io::Error>(WorkerOutput {
+                stdout: out.0,
+                stderr: err.0,
+                exceeded,
+            })
+        };
+        tokio::time::timeout(request.timeout, collect)
+            .await
+            .map_err(|_| ScriptError::Timeout)?
+            .map_err(|e| ScriptError::Failed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl ScriptExecutor for PythonExecutor {
+    async fn execute(&self, request: ScriptRequest) -> ScriptRun {
+        let Ok(_permit) = self.pool.acquire().await else {
+            return ScriptRun {
+                result: Err(ScriptError::Failed(
+                    "python worker pool is closed".to_string(),
+                )),
+                logs: vec![],
+            };
+        };
+
+        let output = match self.spawn(&request).await {
+            Ok(output) => output,
+            Err(err) => {
+                return ScriptRun {
+                    result: Err(err),
+                    logs: vec![],
+                };
+            }
+        };
+
+        let mut logs = LogBuffer::default();
+        String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .filter(|l| !l.is_empty())
+            .for_each(|l| logs.push(l.to_string()));
+        let result = if output.exceeded {
+            logs.set_truncated();
+            Err(ScriptError::Failed(
+                "python output exceeded the limit".to_string(),
+            ))
+        } else {
+            parse_result(&output.stdout)
+        };
+        ScriptRun {
+            result,
+            logs: logs.into_logs(),
+        }
+    }
+}
+
+// 最多读取 cap 个字节，返回是否超出
+async fn read_capped<R: AsyncRead + Unpin>(pipe: Option<R>, cap: usize) -> (Vec<u8>, bool) {
+    let mut buf = Vec::new();
+    if let Some(pipe) = pipe {
+        let _ = pipe.take(cap as u64 + 1).read_to_end(&mut buf).await;
+    }
+    let exceeded = buf.len() > cap;
+    buf.truncate(cap);
+    (buf, exceeded)
+}
+
+fn parse_result(stdout: &[u8]) -> Result<Map<String, Value>, ScriptError> {
+    // 没有结果通常是子进程被资源限制终止
+    let Ok(result) = serde_json::from_slice::<WorkerResult>(stdout) else {
+        return Err(ScriptError::Failed(
+            "python worker exited unexpectedly".to_string(),
+        ));
+    };
+    if result.memory {
+        return Err(ScriptError::MemoryLimit);
+    }
+    if !result.ok {
+        return Err(ScriptError::Failed(result.error.unwrap_or_default()));
+    }
+    match result.output {
+        Some(Value::Object(output)) => Ok(output),
+        None | Some(Value::Null) => Ok(Map::new()),
+        Some(_) => Err(ScriptError::Failed("script must return a dict".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::script::LOGS_TRUNCATED;
+    use std::time::Duration;
+
+    // 测试环境没有 python 时跳过
+    fn python() -> Option<String> {
+        let output = std::process::Command::new("python3")
+            .args(["-c", "import sys; print(sys.executable)"])
+            .output()
+            .ok()?;
+        let path = String::from_utf8(output.stdout).ok()?;
+        Some(path.trim().to_string()).filter(|p| !p.is_empty())
+    }
+
+    async fn execute(python: &str, content: &str, input: Value) -> ScriptRun {
+        let Value::Object(input) = input else {
+            panic!("input must be an object");
+        };
+        let request = ScriptRequest {
+            content: content.to_string(),
+            input,
+            timeout: Duration::from_secs(2),
+        };
+        let pool = Arc::new(Semaphore::new(2));
+        // 内核不支持沙箱时只测试资源限制和审计钩子
+        let sandbox = sandbox::supported();
+        PythonExecutor::new(python.to_string(), 512 * 1024 * 1024, sandbox, pool)
+            .execute(request)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_python_execute() {
+        let Some(python) = python() else {
+            return;
+        };
+
+        let content = "import math\ndef main(input):\n    print('n =', n)\n    return {'root': math.sqrt(input['n'])}";
+        let run = execute(&python, content, json!({"n": 16})).await;
+        assert_eq!(run.result.unwrap()["root"], json!(4.0));
+        assert_eq!(run.logs, vec!["n = 16"]);
+
+        let run = execute(
+            &python,
+            "output = {'upper': name.upper()}",
+            json!({"name": "uvwa"}),
+        )
+        .await;
+        assert_eq!(run.result.unwrap()["upper"], json!("UVWA"));
+    }
+
+    #[tokio::test]
+    async fn test_python_disabled() {
+        use crate::engine::runner::EngineOptions;
+        use crate::engine::script::executor;
+        use crate::models::spec::node::ScriptLanguage;
+
+        let request = ScriptRequest {
+            content: "output = {}".to_string(),
+            input: Map::new(),
+            timeout: Duration::from_secs(2),
+        };
+        let options = EngineOptions {
+            python_enabled: false,
+            ..Default::default()
+        };
+        let run = executor(ScriptLanguage::Python, &options)
+            .execute(request)
+            .await;
+        assert!(matches!(run.result, Err(ScriptError::Failed(m)) if m.contains("disabled")));
+    }
+
+    #[tokio::test]
+    async fn test_python_limits() {
+        let Some(python) = python() else {
+            return;
+        };
+
+        let run = execute(&python, "raise ValueError('boom')", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Failed(m)) if m == "ValueError: boom"));
+
+        let run = execute(&python, "while True:\n    pass", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::Timeout)));
+
+        let run = execute(&python, "data = 'x' * (1024 * 1024 * 1024)", json!({})).await;
+        assert!(matches!(run.result, Err(ScriptError::MemoryLimit)));
+
+        // 无限输出时超出日志上限即结束子进程，不等到超时
+        let run = execute(&python, "while True:\n    print('x' * 10**6)", json!({})).await;
+        assert!(matches!(&run.result, Err(ScriptError::Failed(m)) if m.contains("exceeded")));
+        assert_eq!(run.logs.last().map(String::as_str), Some(LOGS_TRUNCATED));
+    }
+
+    // 审计钩子拦截常见的危险调用，给出明确的错误；可以被 C 层入口绕过，不是隔离手段
+    #[tokio::test]
+    async fn test_python_audit_hook() {
+        let Some(python) = python() else {
+            return;
+        };
+
+        for content in [
+            "open('/etc/hostname').read()",
+            "open('/tmp/uvwa.txt', 'w')",
+            "import socket\nsocket.create_connection(('127.0.0.1', 80))",
+            "import os\nos.system('id')",
+            "import subprocess\nsubprocess.run(['id'])",
+        ] {
+            let run = execute(&python, content, json!({})).await;
+            assert!(
+                matches!(&run.result, Err(ScriptError::Failed(m)) if m.starts_with("PermissionError")),
+                "{}: {:?}",
+                content,
+                run.result
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_python_isolation() {
+        let Some(python) = python() else {
+            return;
+        };
+        if !sandbox::supported() {
+            return;
+        }
+
+        // 不经过审计钩子的调用同样受限：没有宿主用户身份，网络命名空间中只有 lo
+        let content = "import os, socket\noutput = {'uid': os.getuid(), 'ifs': [n for _, n in socket.if_nameindex()]}";
+        let run = execute(&python, content, json!({})).await;
+        let output = run.result.unwrap();
+        assert_eq!(output["uid"], json!(65534));
+        assert_eq!(output["ifs"], json!(["lo"]));
+    }
+}
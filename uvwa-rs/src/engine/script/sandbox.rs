@@ -0,0 +1,208 @@
+use std::ffi::CString;
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+// 子进程的系统级隔离，在 fork 之后、exec 之前调用 restrict：
+// 1. 新的用户、网络、IPC、UTS 命名空间：网络命名空间中只有未启用的 lo，无法访问任何网络；
+//    不同用户命名空间中的进程无法读取宿主进程的 /proc/<pid>/environ 等信息
+// 2. Landlock：只能读取和执行 read_paths 下的文件，不能写入、创建、删除或截断任何文件
+// 3. no_new_privs：无法通过 setuid 程序提权
+// 内核不支持用户命名空间或 Landlock 时 restrict 返回错误，子进程不会在无隔离的情况下启动
+pub struct Sandbox {
+    // 预先准备好的路径和允许的访问权限，fork 之后不能再分配内存
+    rules: Vec<(CString, u64)>,
+}
+
+// 系统库和动态链接器所在的目录
+const SYSTEM_PATHS: [&str; 5] = ["/usr", "/lib", "/lib64", "/bin", "/etc/ld.so.cache"];
+
+// Landlock ABI 1 定义的文件系统访问权限
+const ACCESS_EXECUTE: u64 = 1 << 0;
+const ACCESS_READ_FILE: u64 = 1 << 2;
+const ACCESS_READ_DIR: u64 = 1 << 3;
+const ACCESS_FS_V1: u64 = (1 << 13) - 1;
+// ABI 2 增加跨目录移动（REFER），ABI 3 增加截断（TRUNCATE）
+const ACCESS_REFER: u64 = 1 << 13;
+const ACCESS_TRUNCATE: u64 = 1 << 14;
+
+const CREATE_RULESET_VERSION: u32 = 1 << 0;
+const RULE_PATH_BENEATH: u32 = 1;
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+impl Sandbox {
+    // 允许读取系统库目录和 read_paths，不存在的路径忽略
+    pub fn new(read_paths: &[PathBuf]) -> Self {
+        let system = SYSTEM_PATHS.iter().map(PathBuf::from);
+        let rules = read_paths
+            .iter()
+            .cloned()
+            .chain(system)
+            .filter_map(|path| {
+                let access = match path.metadata() {
+                    Ok(meta) if meta.is_dir() => {
+                        ACCESS_EXECUTE | ACCESS_READ_FILE | ACCESS_READ_DIR
+                    }
+                    Ok(_) => ACCESS_EXECUTE | ACCESS_READ_FILE,
+                    Err(_) => return None,
+                };
+                let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+                Some((path, access))
+            })
+            .collect();
+        Self { rules }
+    }
+
+    // 只能在 fork 之后的子进程中调用，其中只使用 async-signal-safe 的系统调用
+    pub fn restrict(&self) -> io::Result<()> {
+        // SAFETY: 只传入有效的指针和预先分配好的数据，不在子进程中分配内存
+        unsafe {
+            let namespaces =
+                libc::CLONE_NEWUSER | libc::CLONE_NEWNET | libc::CLONE_NEWIPC | libc::CLONE_NEWUTS;
+            check(libc::unshare(namespaces) as libc::c_long)?;
+            check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) as libc::c_long)?;
+
+            let abi = libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0,
+                CREATE_RULESET_VERSION,
+            );
+            let abi = check(abi)?;
+            let mut handled = ACCESS_FS_V1;
+            if abi >= 2 {
+                handled |= ACCESS_REFER;
+            }
+            if abi >= 3 {
+                handled |= ACCESS_TRUNCATE;
+            }
+
+            let attr = RulesetAttr {
+                handled_access_fs: handled,
+            };
+            let ruleset = check(libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                &attr as *const RulesetAttr,
+                size_of::<RulesetAttr>(),
+                0,
+            ))? as libc::c_int;
+
+            let result = self.add_rules(ruleset, handled).and_then(|_| {
+                check(libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0)).map(|_| ())
+            });
+            libc::close(ruleset);
+            result
+        }
+    }
+
+    unsafe fn add_rules(&self, ruleset: libc::c_int, handled: u64) -> io::Result<()> {
+        for (path, access) in &self.rules {
+            // SAFETY: path 是以 NUL 结尾的有效字符串，rule 在调用期间有效
+            unsafe {
+                let fd = libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC);
+                if fd < 0 {
+                    continue;
+                }
+                let rule = PathBeneathAttr {
+                    allowed_access: access & handled,
+                    parent_fd: fd,
+                };
+                let result = libc::syscall(
+                    libc::SYS_landlock_add_rule,
+                    ruleset,
+                    RULE_PATH_BENEATH,
+                    &rule as *const PathBeneathAttr,
+                    0,
+                );
+                libc::close(fd);
+                check(result)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check(result: libc::c_long) -> io::Result<libc::c_long> {
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(result)
+    }
+}
+
+// 可执行文件所在的安装目录（bin 的上一级），用于允许读取解释器自带的标准库
+pub fn install_dir(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    let path = if program.contains('/') {
+        path.to_path_buf()
+    } else {
+        std::env::split_paths(&std::env::var_os("PATH")?)
+            .map(|dir| dir.join(program))
+            .find(|p| p.is_file())?
+    };
+    let path = path.canonicalize().ok()?;
+    Some(path.parent()?.parent()?.to_path_buf())
+}
+
+// 当前内核是否支持沙箱
+#[cfg(test)]
+pub fn supported() -> bool {
+    use std::os::unix::process::CommandExt;
+
+    let sandbox = Sandbox::new(&[]);
+    let mut cmd = std::process::Command::new("/bin/sh");
+    cmd.args(["-c", "exit 0"]);
+    // SAFETY: restrict 只调用 async-signal-safe 的系统调用
+    unsafe {
+        cmd.pre_exec(move || sandbox.restrict());
+    }
+    cmd.status().is_ok_and(|s| s.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    // 在隔离环境中运行 sh 命令，返回是否成功
+    fn sandboxed(script: &str) -> Option<bool> {
+        let sandbox = Sandbox::new(&[]);
+        let mut cmd = Command::new("/bin/sh");
+        cmd.args(["-c", script]).env_clear();
+        // SAFETY: restrict 只调用 async-signal-safe 的系统调用
+        unsafe {
+            cmd.pre_exec(move || sandbox.restrict());
+        }
+        match cmd.output() {
+            Ok(output) => Some(output.status.success()),
+            Err(_) => None,
+        }
+    }
+
+    #[test]
+    fn test_sandbox() {
+        // 测试环境不支持用户命名空间或 Landlock 时跳过
+        if !supported() {
+            return;
+        }
+        // 系统目录可读，其他目录不可读，任何位置都不可写
+        assert_eq!(sandboxed("ls /usr"), Some(true));
+        assert_eq!(sandboxed("cat /etc/hostname"), Some(false));
+        assert_eq!(sandboxed("ls /etc"), Some(false));
+        assert_eq!(sandboxed("echo x > /tmp/uvwa-sandbox"), Some(false));
+        assert!(!Path::new("/tmp/uvwa-sandbox").exists());
+        // 独立的用户命名空间中没有映射宿主用户
+        assert_eq!(sandboxed("test \"$(id -u)\" = 65534"), Some(true));
+    }
+}
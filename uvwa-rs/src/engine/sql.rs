@@ -0,0 +1,259 @@
+use crate::engine::input::coerce;
+use crate::models::spec::variable::{Variable, VariableKind};
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+// 输出变量名：影响的行数，查询时为结果行数
+pub const ROW_NUM: &str = "rowNum";
+
+// 绑定参数后的语句：#{name} 替换为占位符，参数按出现顺序排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlStatement {
+    pub sql: String,
+    pub params: Vec<Value>,
+    // 是否返回结果集
+    pub query: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SqlResult {
+    pub rows: Vec<Map<String, Value>>,
+    pub affected: u64,
+}
+
+// 按连接标识符执行语句，由业务层实现数据源查找和连接池管理
+#[async_trait]
+pub trait SqlExecutor: Send + Sync {
+    async fn execute(&self, conn_key: &str, statement: &SqlStatement) -> Result<SqlResult, String>;
+
+    // 取一个连接并开启事务，供事务容器内的 SQL 节点共用
+    async fn begin(&self, conn_key: &str) -> Result<Box<dyn SqlTransaction>, String>;
+}
+
+// 已开启的事务，提交或回滚后连接归还连接池
+#[async_trait]
+pub trait SqlTransaction: Send {
+    async fn execute(&mut self, statement: &SqlStatement) -> Result<SqlResult, String>;
+
+    async fn commit(self: Box<Self>) -> Result<(), String>;
+
+    async fn rollback(self: Box<Self>) -> Result<(), String>;
+}
+
+// 把 SQL 中的 #{name} 替换为占位符，按输入变量名绑定参数，不拼接字符串；
+// 数组参数展开为多个占位符，便于 IN (#{ids})
+pub fn prepare(content: &str, input: &Map<String, Value>) -> Result<SqlStatement, String> {
+    let mut sql = String::with_capacity(content.len());
+    let mut params = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("#{") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = rest[start + 2..start + len].trim();
+        let value = input
+            .get(name)
+            .ok_or_else(|| format!("parameter {} is not defined in input", name))?;
+
+        sql.push_str(&rest[..start]);
+        match value {
+            Value::Array(items) if items.is_empty() => sql.push_str("NULL"),
+            Value::Array(items) => {
+                sql.push_str(&vec!["?"; items.len()].join(", "));
+                params.extend(items.iter().cloned());
+            }
+            value => {
+                sql.push('?');
+                params.push(value.clone());
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    sql.push_str(rest);
+
+    let query = is_query(&sql);
+    Ok(SqlStatement { sql, params, query })
+}
+
+// 根据第一个关键字判断是否为查询语句
+fn is_query(sql: &str) -> bool {
+    matches!(
+        first_keyword(sql).as_str(),
+        "select" | "show" | "with" | "desc" | "describe" | "explain" | "values"
+    )
+}
+
+// 语句的第一个关键字（小写），跳过开头的注释和括号
+pub fn first_keyword(sql: &str) -> String {
+    let mut text = sql.trim_start();
+    loop {
+        if let Some(rest) = text.strip_prefix("--") {
+            text = rest.split_once('\n').map_or("", |(_, r)| r).trim_start();
+        } else if let Some(rest) = text.strip_prefix("/*") {
+            text = rest.split_once("*/").map_or("", |(_, r)| r).trim_start();
+        } else if let Some(rest) = text.strip_prefix('(') {
+            text = rest.trim_start();
+        } else {
+            break;
+        }
+    }
+    text.chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+// 结果映射到输出变量：
+// rowNum 为行数；ARRAY_OBJECT/ARRAY 为全部行；OBJECT 为第一行；
+// 标量数组取每行的同名列，标量取第一行的同名列；结果只有一列时不要求同名
+pub fn map_output(var: &Variable, result: &SqlResult) -> Result<Value, String> {
+    if var.name == ROW_NUM {
+        return Ok(Value::from(result.affected));
+    }
+
+    let column = |row: &Map<String, Value>| match row.get(&var.name) {
+        Some(value) => Ok(value.clone()),
+        None if row.len() == 1 => Ok(row.values().next().cloned().unwrap_or(Value::Null)),
+        None => Err(format!("column {} is not found in the result", var.name)),
+    };
+    let value = match var.kind {
+        None | Some(VariableKind::ArrayObject) | Some(VariableKind::Array) => Value::Array(
+            result
+                .rows
+                .iter()
+                .map(|r| Value::Object(r.clone()))
+                .collect(),
+        ),
+        Some(VariableKind::Object) => result
+            .rows
+            .first()
+            .map_or(Value::Null, |r| Value::Object(r.clone())),
+        Some(kind) if kind.is_array() => {
+            Value::Array(result.rows.iter().map(column).collect::<Result<_, _>>()?)
+        }
+        Some(_) => match result.rows.first() {
+            Some(row) => column(row)?,
+            None => Value::Null,
+        },
+    };
+    // MySQL 的布尔列返回 0/1
+    let value = match (var.kind, value) {
+        (Some(VariableKind::Boolean), Value::Number(n)) => Value::Bool(n.as_f64() != Some(0.0)),
+        (_, value) => value,
+    };
+
+    match var.kind {
+        Some(kind) => coerce(kind, value)
+            .ok_or_else(|| format!("output {} is not of type {}", var.name, kind)),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn test_prepare() {
+        let input = object(json!({"name": "o'neil", "ids": [1, 2, 3], "empty": [], "age": 18}));
+
+        let statement = prepare(
+            "SELECT * FROM user WHERE name = #{name} AND age > #{ age } AND id IN (#{ids})",
+            &input,
+        )
+        .unwrap();
+        assert_eq!(
+            statement.sql,
+            "SELECT * FROM user WHERE name = ? AND age > ? AND id IN (?, ?, ?)"
+        );
+        assert_eq!(
+            statement.params,
+            vec![json!("o'neil"), json!(18), json!(1), json!(2), json!(3)]
+        );
+        assert!(statement.query);
+
+        let statement = prepare("DELETE FROM user WHERE id IN (#{empty})", &input).unwrap();
+        assert_eq!(statement.sql, "DELETE FROM user WHERE id IN (NULL)");
+        assert!(statement.params.is_empty());
+        assert!(!statement.query);
+
+        assert!(prepare("SELECT #{missing}", &input).is_err());
+    }
+
+    #[test]
+    fn test_is_query() {
+        let table = vec![
+            ("select 1", true),
+            ("  -- comment\n SELECT 1", true),
+            ("/* hint */ (select 1) union (select 2)", true),
+            ("WITH t AS (SELECT 1) SELECT * FROM t", true),
+            ("show tables", true),
+            ("update user set name = 'a'", false),
+            ("insert into user values (1)", false),
+            ("", false),
+        ];
+        for (sql, expected) in table {
+            assert_eq!(is_query(sql), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn test_map_output() {
+        let result = SqlResult {
+            rows: vec![
+                object(json!({"id": 1, "name": "a"})),
+                object(json!({"id": 2, "name": "b"})),
+            ],
+            affected: 2,
+        };
+        let var = |name: &str, kind: &str| -> Variable {
+            serde_json::from_value(json!({"id": name, "name": name, "type": kind})).unwrap()
+        };
+
+        let table = vec![
+            (
+                var("rows", "ARRAY_OBJECT"),
+                json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]),
+            ),
+            (var("rows", "OBJECT"), json!({"id": 1, "name": "a"})),
+            (var("name", "ARRAY_STRING"), json!(["a", "b"])),
+            (var("name", "STRING"), json!("a")),
+            (var("rowNum", "INTEGER"), json!(2)),
+            (var("id", "BOOLEAN"), json!(true)),
+        ];
+        for (var, expected) in table {
+            assert_eq!(map_output(&var, &result).unwrap(), expected, "{}", var.name);
+        }
+
+        // 只有一列时不要求列名与变量名相同
+        let count = SqlResult {
+            rows: vec![object(json!({"COUNT(*)": 2}))],
+            affected: 1,
+        };
+        assert_eq!(map_output(&var("total", "LONG"), &count).unwrap(), json!(2));
+        assert_eq!(
+            map_output(&var("total", "ARRAY_STRING"), &count).unwrap(),
+            json!(["2"])
+        );
+        assert!(map_output(&var("total", "LONG"), &result).is_err());
+
+        let empty = SqlResult::default();
+        assert_eq!(
+            map_output(&var("rows", "ARRAY_OBJECT"), &empty).unwrap(),
+            json!([])
+        );
+        assert_eq!(
+            map_output(&var("rows", "INTEGER"), &empty).unwrap(),
+            json!(null)
+        );
+        assert!(map_output(&var("name", "INTEGER"), &result).is_err());
+    }
+}
@@ -0,0 +1,9 @@
+use crate::models::spec::AppSpec;
+use async_trait::async_trait;
+
+// 按应用 id 加载子流程，由业务层实现版本查找；
+// version 为空时取最新发布的版本，否则取指定的版本号
+#[async_trait]
+pub trait FlowLoader: Send + Sync {
+    async fn load(&self, flow_id: &str, version: Option<&str>) -> Result<AppSpec, String>;
+}
@@ -0,0 +1,94 @@
+use crate::engine::vars::Scope;
+use crate::models::spec::Node;
+use crate::models::spec::node::{AssignScope, NodeKind};
+use crate::models::spec::variable::{Variable, conversation_key, var_key};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// 单次运行最多记录的节点数，超出后不再记录，避免长循环占用过多内存
+pub const MAX_TRACE_NODES: usize = 1000;
+
+// 节点执行记录，按节点开始执行的顺序排列
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeTrace {
+    pub node_id: String,
+    pub node_type: &'static str,
+    pub input: Map<String, Value>,
+    pub output: Map<String, Value>,
+    // 执行耗时（毫秒），容器节点包含其内部节点
+    pub elapsed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // 调试模式下代码节点的控制台输出
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+}
+
+impl NodeTrace {
+    pub fn new(node: &Node, input: Map<String, Value>) -> Self {
+        Self {
+            node_id: node.id.clone(),
+            node_type: node.kind.type_name(),
+            input,
+            output: Map::new(),
+            elapsed: 0,
+            error: None,
+            logs: Vec::new(),
+        }
+    }
+}
+
+// 节点的输入：开始节点为调用参数，代码/SQL/子流程按输入变量名，其他节点按引用的变量 key
+pub fn node_input(node: &Node, scope: &Scope, input: &Map<String, Value>) -> Map<String, Value> {
+    let named = |vars: &[Variable]| -> Map<String, Value> {
+        vars.iter()
+            .map(|v| (v.name.clone(), scope.resolve(v.value.as_deref())))
+            .collect()
+    };
+    match &node.kind {
+        NodeKind::Start(_) => input.clone(),
+        NodeKind::Code(code) => named(&code.input),
+        NodeKind::Sql(sql) => named(&sql.input),
+        NodeKind::SubFlow(sub) => named(&sub.input),
+        // 结束节点的输入即输出变量，记录在输出中
+        NodeKind::End(_) => Map::new(),
+        kind => kind
+            .references()
+            .into_iter()
+            .map(|key| {
+                let value = scope.resolve(Some(&key));
+                (key, value)
+            })
+            .collect(),
+    }
+}
+
+// 节点的输出：声明的输出变量按名称；结束节点为输出变量，赋值节点为赋值后的目标变量
+pub fn node_output(node: &Node, scope: &Scope) -> Map<String, Value> {
+    match &node.kind {
+        NodeKind::End(end) => end
+            .output
+            .iter()
+            .flat_map(|o| &o.vars)
+            .map(|v| (v.name.clone(), scope.resolve(v.value.as_deref())))
+            .collect(),
+        NodeKind::Assign(assign) => assign
+            .items
+            .iter()
+            .map(|item| {
+                let key = match item.scope {
+                    AssignScope::Workflow => item.target.clone(),
+                    AssignScope::Conversation => conversation_key(&item.target),
+                };
+                let value = scope.resolve(Some(&key));
+                (key, value)
+            })
+            .collect(),
+        kind => kind
+            .variables()
+            .iter()
+            .map(|v| (v.name.clone(), scope.resolve(Some(&var_key(&node.id, v)))))
+            .collect(),
+    }
+}
@@ -0,0 +1,34 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+// 运行时变量表：以变量 id 为 key 保存各节点产生的值
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    values: HashMap<String, Value>,
+}
+
+impl Scope {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: Value) {
+        self.values.insert(key.into(), value);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        self.values.remove(key)
+    }
+
+    // 解析变量引用，未赋值的变量视为 null
+    pub fn resolve(&self, reference: Option<&str>) -> Value {
+        reference
+            .and_then(|r| self.values.get(r))
+            .cloned()
+            .unwrap_or(Value::Null)
+    }
+
+    pub fn values(&self) -> &HashMap<String, Value> {
+        &self.values
+    }
+}
@@ -1,4 +1,8 @@
+use crate::business::app_run::history;
+use crate::business::datasource::credential;
 use crate::config::AppConfig;
+use crate::engine::runner::EngineOptions;
+use crate::utils::secret::Secret;
 use crate::web::server::WebServer;
 use rivus_logger::LoggerConfig;
 use uorm::driver_manager::U;
@@ -8,6 +12,7 @@ use uorm::udbc::mysql::pool::MysqlDriver;
 
 mod business;
 mod config;
+mod engine;
 mod models;
 mod routes;
 mod utils;
@@ -43,7 +48,37 @@ async fn main() -> anyhow::Result<()> {
     let driver = driver.build()?;
     U.register(driver)?;
 
-    // 3. 启动服务
+    // 3. 初始化流程执行限制
+    if let Some(engine) = conf.engine {
+        EngineOptions::init(EngineOptions {
+            max_loop_iterations: engine.max_loop_iterations,
+            script_timeout: engine.script_timeout,
+            script_memory_limit: (engine.script_memory_mb * 1024 * 1024) as usize,
+            python_enabled: engine.python_enabled,
+            python_sandbox: engine.python_sandbox,
+            python: engine.python,
+            python_workers: engine.python_workers,
+            python_memory_limit: (engine.python_memory_mb * 1024 * 1024) as usize,
+            max_subflow_depth: engine.max_subflow_depth,
+            sql_max_rows: engine.sql_max_rows,
+        });
+    }
+
+    // 4. 初始化凭证加密，配置了旧密钥时用主密钥重新加密已保存的凭证
+    let secret = Secret::new(&conf.secret.master_key, &conf.secret.previous_keys())?;
+    let rotating = secret.rotating();
+    Secret::init(secret);
+    if rotating {
+        let count = credential::reencrypt_passwords().await?;
+        tracing::info!("re-encrypted {} datasource credentials", count);
+    }
+
+    // 5. 定期清理过期的运行记录
+    if let Some(retention) = conf.history {
+        tokio::spawn(history::cleanup(retention.retention_days));
+    }
+
+    // 6. 启动服务
     WebServer::new(&conf.server)
         .mount(routes::router())
         .layer_i18n()
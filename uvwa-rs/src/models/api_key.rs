@@ -0,0 +1,54 @@
+use crate::business::api_key::api_key_dao::ApiKey;
+use crate::web::ts_str::{option_str_or_number, to_str};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyReq {
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub app_id: Option<u64>,
+}
+
+// 指定 appId 时只能调用该应用，不指定时可调用工作空间内的所有应用
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateReq {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub app_id: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub app_id: u64,
+    pub name: String,
+    pub key_hint: String,
+    pub create_time: Option<String>,
+}
+
+impl From<ApiKey> for ApiKeyResp {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id,
+            app_id: key.app_id,
+            name: key.name,
+            key_hint: key.key_hint,
+            create_time: key.create_time,
+        }
+    }
+}
+
+// 创建时返回密钥明文，之后无法再次查看
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyCreateResp {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResp,
+    pub key: String,
+}
@@ -1,7 +1,9 @@
 use crate::web::ts_str::to_number;
 use crate::web::ts_str::vec_to_number;
-use crate::business::app::app_dao::{App, AppVersion};
+use crate::business::app::app_dao::{App, AppVersion, AppVersionLog};
 use crate::utils::id::Id;
+use crate::utils::semver::{Bump, Version};
+use crate::web::ts_str::option_str_or_number;
 use crate::web::ts_str::to_str;
 use crate::web::ts_str::vec_to_str;
 use serde::{Deserialize, Serialize};
@@ -62,43 +64,127 @@ pub struct AppCloneReq {
     pub description: Option<String>,
 }
 
+// 发布版本：指定版本号，或按 bump 从已发布的最高版本自动递增，两者只能选一个
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppVersionReq {
-    pub version: String,
+    pub version: Option<String>,
+    pub bump: Option<Bump>,
     pub description: Option<String>,
 }
 
-impl From<(App, AppVersionReq, String)> for AppVersion {
-    fn from((app, req, spec): (App, AppVersionReq, String)) -> Self {
-        let (major, minor, patch, pre_release) = parse_version(&req.version);
+impl From<(App, Version, AppVersionReq, String)> for AppVersion {
+    fn from((app, version, req, spec): (App, Version, AppVersionReq, String)) -> Self {
         Self {
             id: Id::next_id().unwrap_or_default(),
             tenant_id: app.tenant_id,
             workspace_id: app.workspace_id,
             app_id: app.id,
-            version: req.version,
-            major,
-            minor,
-            patch,
-            pre_release,
+            version: version.to_string(),
+            major: i32::try_from(version.major).ok(),
+            minor: i32::try_from(version.minor).ok(),
+            patch: i32::try_from(version.patch).ok(),
+            pre_release: version.pre_release(),
             spec: Some(spec),
             description: req.description,
             is_latest: true,
+            create_time: None,
+        }
+    }
+}
+
+// 版本列表不返回草稿内容，版本详情返回
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppVersionResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub app_id: u64,
+    pub version: String,
+    pub major: Option<i32>,
+    pub minor: Option<i32>,
+    pub patch: Option<i32>,
+    pub pre_release: Option<String>,
+    pub description: Option<String>,
+    pub is_latest: bool,
+    pub create_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<String>,
+}
+
+impl From<AppVersion> for AppVersionResp {
+    fn from(version: AppVersion) -> Self {
+        Self {
+            id: version.id,
+            app_id: version.app_id,
+            version: version.version,
+            major: version.major,
+            minor: version.minor,
+            patch: version.patch,
+            pre_release: version.pre_release,
+            description: version.description,
+            is_latest: version.is_latest,
+            create_time: version.create_time,
+            spec: version.spec,
+        }
+    }
+}
+
+// 版本对比的目标版本，不传时与草稿对比
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppVersionDiffQuery {
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub to: Option<u64>,
+}
+
+// 版本回滚操作，审计记录中保存为小写字符串
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VersionAction {
+    // 把版本的草稿恢复为当前草稿
+    Restore,
+    // 把版本重新设为最新版本
+    Promote,
+}
+
+impl VersionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Restore => "restore",
+            Self::Promote => "promote",
         }
     }
 }
 
-fn parse_version(version: &str) -> (Option<i32>, Option<i32>, Option<i32>, Option<String>) {
-    let (main, pre) = match version.find('-') {
-        Some(idx) => (&version[..idx], Some(version[idx + 1..].to_string())),
-        None => (version, None),
-    };
-    let parts: Vec<&str> = main.split('.').collect();
-    let major = parts.get(0).and_then(|s| s.parse().ok());
-    let minor = parts.get(1).and_then(|s| s.parse().ok());
-    let patch = parts.get(2).and_then(|s| s.parse().ok());
-    (major, minor, patch, pre)
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppVersionLogResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub version_id: u64,
+    pub version: String,
+    pub action: String,
+    #[serde(serialize_with = "to_str")]
+    pub previous_version_id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub user_id: u64,
+    pub create_time: Option<String>,
+}
+
+impl From<AppVersionLog> for AppVersionLogResp {
+    fn from(log: AppVersionLog) -> Self {
+        Self {
+            id: log.id,
+            version_id: log.version_id,
+            version: log.version,
+            action: log.action,
+            previous_version_id: log.previous_version_id,
+            user_id: log.user_id,
+            create_time: log.create_time,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Param, Default)]
@@ -0,0 +1,282 @@
+use crate::business::app_run::run_dao::{AppRun, AppRunNode};
+use crate::engine::response::EndResponse;
+use crate::engine::runner::RunOutput;
+use crate::engine::trace::NodeTrace;
+use crate::web::r::R;
+use crate::web::ts_str::{option_str_or_number, to_str};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uorm::Param;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunReq {
+    #[serde(default)]
+    pub input: Map<String, Value>,
+    // 对话应用的会话变量，传入上一轮返回的值
+    #[serde(default)]
+    pub conversation: Map<String, Value>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunResp {
+    // 按结束节点配置整理的响应，与发布的接口返回的内容一致
+    pub response: EndResponse,
+    pub vars: Map<String, Value>,
+    // 文本模式下渲染后的文本
+    pub text: Option<String>,
+    // 各节点的执行记录：输入、输出、耗时和调试模式下的日志
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trace: Vec<NodeTrace>,
+    // 本轮结束时的会话变量，下一轮对话时传回
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    pub conversation: Map<String, Value>,
+}
+
+impl From<RunOutput> for AppRunResp {
+    fn from(output: RunOutput) -> Self {
+        Self {
+            text: output.response.text().map(|t| t.to_string()),
+            response: output.response,
+            vars: output.vars,
+            trace: output.trace,
+            conversation: output.conversation,
+        }
+    }
+}
+
+// 单独测试节点的参数：node 为编辑器中的节点定义，不传时使用草稿中的节点；
+// input 按节点输入变量名传值
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeTestReq {
+    pub node: Option<Value>,
+    #[serde(default)]
+    pub input: Map<String, Value>,
+}
+
+// 运行状态，运行记录中保存为小写字符串
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+    // 客户端断开或服务关闭时中止
+    Cancelled,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+// 发起运行的一方
+#[derive(Debug, Clone, Copy)]
+pub enum RunCaller {
+    // 登录用户在控制台运行
+    User(u64),
+    // 外部系统通过发布的接口调用，记录使用的 API 密钥
+    ApiKey(u64),
+}
+
+impl RunCaller {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::User(_) => "user",
+            Self::ApiKey(_) => "api_key",
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        match self {
+            Self::User(id) | Self::ApiKey(id) => *id,
+        }
+    }
+}
+
+// 运行记录查询，时间格式为 yyyy-MM-dd HH:mm:ss，包含起始时间不包含结束时间
+#[derive(Deserialize, Param, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunQuery {
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub app_id: Option<u64>,
+    pub status: Option<String>,
+    pub start_from: Option<String>,
+    pub start_to: Option<String>,
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub page_no: Option<u64>,
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub page_size: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunRecordResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub app_id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub version_id: u64,
+    pub version: String,
+    pub status: String,
+    pub error_code: Option<i32>,
+    pub error_message: Option<String>,
+    pub caller_type: String,
+    #[serde(serialize_with = "to_str")]
+    pub caller_id: u64,
+    pub elapsed: u64,
+    pub start_time: Option<String>,
+    pub finish_time: Option<String>,
+}
+
+impl From<&AppRun> for AppRunRecordResp {
+    fn from(run: &AppRun) -> Self {
+        Self {
+            id: run.id,
+            app_id: run.app_id,
+            version_id: run.version_id,
+            version: run.version.clone(),
+            status: run.status.clone(),
+            error_code: run.error_code,
+            error_message: run.error_message.clone(),
+            caller_type: run.caller_type.clone(),
+            caller_id: run.caller_id,
+            elapsed: run.elapsed,
+            start_time: run.start_time.clone(),
+            finish_time: run.finish_time.clone(),
+        }
+    }
+}
+
+// 运行详情：运行记录及其调用参数、输出和各节点的执行记录
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunDetailResp {
+    #[serde(flatten)]
+    pub record: AppRunRecordResp,
+    pub input: Value,
+    pub output: Value,
+    pub nodes: Vec<AppRunNodeResp>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppRunNodeResp {
+    pub node_id: String,
+    pub node_type: String,
+    pub input: Value,
+    pub output: Value,
+    pub elapsed: u64,
+    pub error: Option<String>,
+    pub logs: Value,
+}
+
+impl From<AppRunNode> for AppRunNodeResp {
+    fn from(node: AppRunNode) -> Self {
+        Self {
+            node_id: node.node_id,
+            node_type: node.node_type,
+            input: parse_json(node.input.as_deref()),
+            output: parse_json(node.output.as_deref()),
+            elapsed: node.elapsed,
+            error: node.error,
+            logs: parse_json(node.logs.as_deref()),
+        }
+    }
+}
+
+impl From<(AppRun, Vec<AppRunNode>)> for AppRunDetailResp {
+    fn from((run, nodes): (AppRun, Vec<AppRunNode>)) -> Self {
+        Self {
+            record: (&run).into(),
+            input: parse_json(run.input.as_deref()),
+            output: parse_json(run.output.as_deref()),
+            nodes: nodes.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+// 保存的 JSON 无法解析时按空值返回
+fn parse_json(json: Option<&str>) -> Value {
+    json.and_then(|j| serde_json::from_str(j).ok())
+        .unwrap_or(Value::Null)
+}
+
+// 调试运行草稿的参数，断点为节点 id
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugCreateReq {
+    #[serde(default)]
+    pub input: Map<String, Value>,
+    #[serde(default)]
+    pub conversation: Map<String, Value>,
+    #[serde(default)]
+    pub breakpoints: Vec<String>,
+    // 在第一个节点执行前暂停
+    #[serde(default)]
+    pub pause_on_start: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugBreakpointsReq {
+    pub breakpoints: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DebugStatus {
+    Running,
+    // 在断点或单步时暂停在节点执行之前
+    Paused,
+    Succeeded,
+    Failed,
+}
+
+// 调试运行的状态：暂停时为即将执行的节点和当前的变量表，结束时为运行结果或错误
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugState {
+    pub status: DebugStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    // 变量表快照，key 与节点可引用变量的 varId 相同
+    pub scope: Map<String, Value>,
+    pub trace: Vec<NodeTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<AppRunResp>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<R<()>>,
+}
+
+impl DebugState {
+    pub fn running() -> Self {
+        Self {
+            status: DebugStatus::Running,
+            node_id: None,
+            scope: Map::new(),
+            trace: Vec::new(),
+            output: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugSessionResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(serialize_with = "to_str")]
+    pub app_id: u64,
+    pub breakpoints: Vec<String>,
+    #[serde(flatten)]
+    pub state: DebugState,
+}
@@ -0,0 +1,140 @@
+use crate::business::datasource::connector::ConnectOptions;
+use crate::business::datasource::credential::PASSWORD_MASK;
+use crate::business::datasource::datasource_dao::Connection;
+use crate::utils::id::Id;
+use crate::web::ts_str::to_str;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use uorm::Param;
+use validator::Validate;
+
+// 连接标识符只能包含字母、数字、下划线和横线
+static CONN_KEY_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap());
+
+#[derive(Deserialize, Param, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionReq {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub conn_type: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCreateReq {
+    #[serde(rename = "key")]
+    #[validate(length(min = 1, max = 50), regex(path = *CONN_KEY_PATTERN, code = "pattern"))]
+    pub conn_key: String,
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub conn_type: Option<String>,
+    #[validate(length(min = 1, max = 200))]
+    pub url: String,
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    #[validate(length(min = 1, max = 100))]
+    pub password: String,
+}
+
+impl From<(u64, u64, ConnectionCreateReq)> for Connection {
+    fn from((tenant_id, workspace_id, req): (u64, u64, ConnectionCreateReq)) -> Self {
+        Self {
+            id: Id::next_id().unwrap_or_default(),
+            tenant_id,
+            workspace_id,
+            conn_key: req.conn_key,
+            name: req.name,
+            conn_type: req.conn_type,
+            url: req.url,
+            username: req.username,
+            password: req.password,
+            create_time: None,
+        }
+    }
+}
+
+// 密码为空或为掩码时保留原密码
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionUpdateReq {
+    #[serde(rename = "key")]
+    #[validate(length(min = 1, max = 50), regex(path = *CONN_KEY_PATTERN, code = "pattern"))]
+    pub conn_key: String,
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub conn_type: Option<String>,
+    #[validate(length(min = 1, max = 200))]
+    pub url: String,
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    #[validate(length(max = 100))]
+    pub password: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestReq {
+    #[serde(rename = "type")]
+    pub conn_type: Option<String>,
+    #[validate(length(min = 1, max = 200))]
+    pub url: String,
+    #[validate(length(min = 1, max = 50))]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+impl From<ConnectionTestReq> for ConnectOptions {
+    fn from(req: ConnectionTestReq) -> Self {
+        Self {
+            conn_type: req.conn_type,
+            url: req.url,
+            username: req.username,
+            password: req.password,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResp {
+    // 建立连接并执行查询的耗时（毫秒）
+    pub latency: u64,
+    pub version: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionResp {
+    #[serde(serialize_with = "to_str")]
+    pub id: u64,
+    #[serde(rename = "key")]
+    pub conn_key: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub conn_type: Option<String>,
+    pub url: String,
+    pub username: String,
+    // 密码不返回明文或密文，只返回掩码
+    pub password: String,
+    pub create_time: Option<String>,
+}
+
+impl From<Connection> for ConnectionResp {
+    fn from(conn: Connection) -> Self {
+        Self {
+            id: conn.id,
+            conn_key: conn.conn_key,
+            name: conn.name,
+            conn_type: conn.conn_type,
+            url: conn.url,
+            username: conn.username,
+            password: PASSWORD_MASK.to_string(),
+            create_time: conn.create_time,
+        }
+    }
+}
@@ -1,5 +1,10 @@
+pub mod api_key;
 pub mod app;
+pub mod app_run;
 pub mod context;
+pub mod datasource;
+pub mod page;
+pub mod spec;
 pub mod user;
 pub mod workspace;
 pub mod workspace_folder;
@@ -0,0 +1,46 @@
+use serde::Serialize;
+
+// 默认每页数量
+pub const DEFAULT_PAGE_SIZE: u64 = 20;
+// 每页数量上限
+pub const MAX_PAGE_SIZE: u64 = 200;
+
+// 分页列表返回参数
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageResp<T: Serialize> {
+    pub total: u64,
+    pub items: Vec<T>,
+}
+
+impl<T: Serialize> PageResp<T> {
+    pub fn new(total: u64, items: Vec<T>) -> Self {
+        Self { total, items }
+    }
+}
+
+// 根据页码（从 1 开始）和每页数量计算 LIMIT 的 offset 与 limit
+pub fn page_bounds(page_no: Option<u64>, page_size: Option<u64>) -> (u64, u64) {
+    let page_no = page_no.unwrap_or(1).max(1);
+    let page_size = page_size
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .min(MAX_PAGE_SIZE);
+    ((page_no - 1) * page_size, page_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_bounds() {
+        assert_eq!(page_bounds(None, None), (0, DEFAULT_PAGE_SIZE));
+        assert_eq!(page_bounds(Some(0), Some(0)), (0, DEFAULT_PAGE_SIZE));
+        assert_eq!(page_bounds(Some(3), Some(10)), (20, 10));
+        assert_eq!(
+            page_bounds(Some(2), Some(1000)),
+            (MAX_PAGE_SIZE, MAX_PAGE_SIZE)
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// 只比较节点的这些字段，位置、尺寸、选中状态等编辑器状态不算修改
+const NODE_FIELDS: [&str; 3] = ["type", "parentId", "data"];
+const EDGE_FIELDS: [&str; 4] = ["source", "target", "sourceHandle", "targetHandle"];
+
+// 两份草稿之间的结构差异，按 id 对应节点和连线
+#[derive(Serialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecDiff {
+    pub nodes: DiffSet,
+    pub edges: DiffSet,
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSet {
+    pub added: Vec<DiffItem>,
+    pub removed: Vec<DiffItem>,
+    pub changed: Vec<DiffItem>,
+}
+
+// 节点带类型和标题，连线带起止节点；修改时 fields 为变化的字段，data 内的字段为 data.xxx
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffItem {
+    pub id: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<String>,
+}
+
+// 比较 base 到 target 的变化，两者都是草稿文档
+pub fn diff(base: &str, target: &str) -> Result<SpecDiff, serde_json::Error> {
+    let base: Value = serde_json::from_str(base)?;
+    let target: Value = serde_json::from_str(target)?;
+    Ok(SpecDiff {
+        nodes: diff_items(&base, &target, "nodes", &NODE_FIELDS),
+        edges: diff_items(&base, &target, "edges", &EDGE_FIELDS),
+    })
+}
+
+fn diff_items(base: &Value, target: &Value, key: &str, fields: &[&str]) -> DiffSet {
+    let base = items(base, key);
+    let target = items(target, key);
+    let mut set = DiffSet::default();
+    for (id, item) in &target {
+        match base.iter().find(|(base_id, _)| base_id == id) {
+            None => set.added.push(diff_item(id, item, Vec::new())),
+            Some((_, old)) => {
+                let changed = changed_fields(old, item, fields);
+                if !changed.is_empty() {
+                    set.changed.push(diff_item(id, item, changed));
+                }
+            }
+        }
+    }
+    for (id, item) in &base {
+        if !target.iter().any(|(target_id, _)| target_id == id) {
+            set.removed.push(diff_item(id, item, Vec::new()));
+        }
+    }
+    set
+}
+
+// 带 id 的对象，保持文档中的顺序
+fn items<'v>(spec: &'v Value, key: &str) -> Vec<(&'v str, &'v Map<String, Value>)> {
+    spec.get(key)
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_object())
+        .filter_map(|o| Some((o.get("id")?.as_str()?, o)))
+        .collect()
+}
+
+fn changed_fields(
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    fields: &[&str],
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    for field in fields {
+        let (old, new) = (field_value(old, field), field_value(new, field));
+        if old == new {
+            continue;
+        }
+        match (old, new) {
+            (Value::Object(old), Value::Object(new)) => {
+                let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+                keys.sort();
+                keys.dedup();
+                changed.extend(
+                    keys.into_iter()
+                        .filter(|k| old.get(*k) != new.get(*k))
+                        .map(|k| format!("{}.{}", field, k)),
+                );
+            }
+            _ => changed.push(field.to_string()),
+        }
+    }
+    changed
+}
+
+// 缺失的字段与 null 相同
+fn field_value<'v>(item: &'v Map<String, Value>, field: &str) -> &'v Value {
+    item.get(field).unwrap_or(&Value::Null)
+}
+
+fn diff_item(id: &str, item: &Map<String, Value>, fields: Vec<String>) -> DiffItem {
+    let text = |value: Option<&Value>| value.and_then(|v| v.as_str()).map(|s| s.to_string());
+    DiffItem {
+        id: id.to_string(),
+        kind: text(item.get("type")),
+        title: text(item.get("data").and_then(|d| d.get("title"))),
+        source: text(item.get("source")),
+        target: text(item.get("target")),
+        fields,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff() {
+        let base = json!({
+            "nodes": [
+                {"id": "s", "type": "start", "position": {"x": 0, "y": 0}, "data": {"title": "开始"}},
+                {"id": "c", "type": "code", "data": {"title": "计算", "content": "a", "timeout": 1}},
+                {"id": "e", "type": "end", "data": {}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "c"},
+                {"id": "2", "source": "c", "target": "e"}
+            ]
+        });
+        let target = json!({
+            "nodes": [
+                {"id": "s", "type": "start", "position": {"x": 100, "y": 0}, "data": {"title": "开始"}},
+                {"id": "c", "type": "code", "data": {"title": "计算", "content": "b"}},
+                {"id": "x", "type": "sql", "data": {"title": "查询"}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "c"},
+                {"id": "3", "source": "c", "target": "x"}
+            ]
+        });
+        let diff = diff(&base.to_string(), &target.to_string()).unwrap();
+
+        // 只移动位置不算修改
+        let ids =
+            |items: &[DiffItem]| -> Vec<String> { items.iter().map(|i| i.id.clone()).collect() };
+        assert_eq!(ids(&diff.nodes.added), vec!["x"]);
+        assert_eq!(ids(&diff.nodes.removed), vec!["e"]);
+        assert_eq!(ids(&diff.nodes.changed), vec!["c"]);
+        assert_eq!(
+            diff.nodes.changed[0].fields,
+            vec!["data.content", "data.timeout"]
+        );
+        assert_eq!(diff.nodes.added[0].title.as_deref(), Some("查询"));
+
+        assert_eq!(ids(&diff.edges.added), vec!["3"]);
+        assert_eq!(ids(&diff.edges.removed), vec!["2"]);
+        assert!(diff.edges.changed.is_empty());
+        assert_eq!(diff.edges.added[0].target.as_deref(), Some("x"));
+
+        assert_eq!(
+            super::diff(&base.to_string(), &base.to_string()).unwrap(),
+            SpecDiff::default()
+        );
+        assert!(super::diff("{", "{}").is_err());
+    }
+}
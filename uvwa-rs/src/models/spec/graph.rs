@@ -0,0 +1,153 @@
+use crate::models::spec::node::NodeKind;
+use crate::models::spec::variable::{Variable, var_key};
+use crate::models::spec::{AppSpec, Edge, Node};
+use std::collections::HashMap;
+
+// 草稿的图索引：按 id 查节点、按节点查出入边、按容器查子节点
+pub struct SpecGraph<'a> {
+    nodes: HashMap<&'a str, &'a Node>,
+    outgoing: HashMap<&'a str, Vec<&'a Edge>>,
+    incoming: HashMap<&'a str, Vec<&'a Edge>>,
+    children: HashMap<&'a str, Vec<&'a Node>>,
+    conversation: &'a [Variable],
+}
+
+impl<'a> SpecGraph<'a> {
+    pub fn new(spec: &'a AppSpec) -> Self {
+        let mut graph = Self {
+            nodes: HashMap::with_capacity(spec.nodes.len()),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+            children: HashMap::new(),
+            conversation: &spec.conversation_variables,
+        };
+
+        for node in &spec.nodes {
+            graph.nodes.insert(node.id.as_str(), node);
+            if let Some(parent_id) = &node.parent_id {
+                graph
+                    .children
+                    .entry(parent_id.as_str())
+                    .or_default()
+                    .push(node);
+            }
+        }
+
+        for edge in &spec.edges {
+            graph
+                .outgoing
+                .entry(edge.source.as_str())
+                .or_default()
+                .push(edge);
+            graph
+                .incoming
+                .entry(edge.target.as_str())
+                .or_default()
+                .push(edge);
+        }
+        graph
+    }
+
+    pub fn node(&self, id: &str) -> Option<&'a Node> {
+        self.nodes.get(id).copied()
+    }
+
+    // 会话变量声明
+    pub fn conversation(&self) -> &'a [Variable] {
+        self.conversation
+    }
+
+    // 按变量表中的 key 查找节点声明的变量
+    pub fn variable(&self, key: &str) -> Option<&'a Variable> {
+        self.nodes.values().find_map(|node| {
+            node.kind
+                .variables()
+                .iter()
+                .find(|var| var_key(&node.id, var) == key)
+        })
+    }
+
+    // 顶层的开始节点
+    pub fn start(&self) -> Option<&'a Node> {
+        self.nodes
+            .values()
+            .copied()
+            .find(|n| n.parent_id.is_none() && matches!(n.kind, NodeKind::Start(_)))
+    }
+
+    pub fn outgoing(&self, id: &str) -> &[&'a Edge] {
+        self.outgoing
+            .get(id)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn incoming(&self, id: &str) -> &[&'a Edge] {
+        self.incoming
+            .get(id)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn children(&self, group_id: &str) -> &[&'a Node] {
+        self.children
+            .get(group_id)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+
+    // 从节点指定出口出发的下游节点，handle 为 None 时匹配未指定 handle 的连线
+    pub fn next(&self, id: &str, handle: Option<&str>) -> Vec<&'a Node> {
+        self.outgoing(id)
+            .iter()
+            .filter(|e| e.source_handle.as_deref() == handle)
+            .filter_map(|e| self.node(&e.target))
+            .collect()
+    }
+
+    // 所有出口的下游节点
+    pub fn next_all(&self, id: &str) -> Vec<&'a Node> {
+        self.outgoing(id)
+            .iter()
+            .filter_map(|e| self.node(&e.target))
+            .collect()
+    }
+
+    // 容器的入口：优先使用 group-start 节点，否则取容器内没有入边的节点
+    pub fn group_entries(&self, group_id: &str) -> Vec<&'a Node> {
+        let children = self.children(group_id);
+        if let Some(start) = children
+            .iter()
+            .find(|n| matches!(n.kind, NodeKind::GroupStart))
+        {
+            return vec![*start];
+        }
+
+        let mut entries: Vec<&'a Node> = children
+            .iter()
+            .copied()
+            .filter(|n| !matches!(n.kind, NodeKind::Note(_)))
+            .filter(|n| {
+                self.incoming(&n.id).iter().all(|e| {
+                    self.node(&e.source).and_then(|s| s.parent_id.as_deref()) != Some(group_id)
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        entries
+    }
+
+    // 沿 parentId 向上查找最近的循环节点
+    pub fn enclosing_loop(&self, id: &str) -> Option<&'a Node> {
+        let mut current = self.node(id)?.parent_id.as_deref();
+        // 以节点数为上限，防止 parentId 成环
+        for _ in 0..self.nodes.len() {
+            let parent = self.node(current?)?;
+            if matches!(parent.kind, NodeKind::Loop(_)) {
+                return Some(parent);
+            }
+            current = parent.parent_id.as_deref();
+        }
+        None
+    }
+}
@@ -0,0 +1,361 @@
+use crate::core::code::Code;
+use crate::models::spec::graph::SpecGraph;
+use crate::models::spec::node::NodeKind;
+use crate::models::spec::{AppSpec, SpecIssue};
+use std::collections::{HashMap, HashSet};
+
+impl AppSpec {
+    // 图结构检查：开始/结束节点、悬空连线、孤立节点、环、循环控制节点的位置以及变量引用
+    pub fn lint(&self) -> Vec<SpecIssue> {
+        let graph = SpecGraph::new(self);
+        let mut issues = Vec::new();
+
+        self.lint_start(&mut issues);
+        self.lint_parents(&graph, &mut issues);
+        self.lint_edges(&graph, &mut issues);
+        self.lint_reachability(&graph, &mut issues);
+        self.lint_cycles(&graph, &mut issues);
+        self.lint_loop_controls(&graph, &mut issues);
+        self.lint_references(&graph, &mut issues);
+        issues
+    }
+
+    // 容器内的开始节点单独报告，顶层开始节点必须有且只有一个
+    fn lint_start(&self, issues: &mut Vec<SpecIssue>) {
+        let mut starts = vec![];
+        for node in &self.nodes {
+            if !matches!(node.kind, NodeKind::Start(_)) {
+                continue;
+            }
+            match &node.parent_id {
+                Some(parent_id) => issues.push(SpecIssue::node(
+                    &node.id,
+                    Code::SpecStartNested,
+                    vec![("parent".to_string(), parent_id.clone())],
+                )),
+                None => starts.push(node),
+            }
+        }
+
+        match starts.len() {
+            0 => issues.push(SpecIssue::spec(Code::SpecStartMissing, vec![])),
+            1 => {}
+            _ => {
+                for node in starts {
+                    issues.push(SpecIssue::node(&node.id, Code::SpecStartMultiple, vec![]));
+                }
+            }
+        }
+    }
+
+    fn lint_parents(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        for node in &self.nodes {
+            let Some(parent_id) = &node.parent_id else {
+                continue;
+            };
+            let is_group = graph.node(parent_id).is_some_and(|p| p.kind.is_group());
+            if !is_group {
+                issues.push(SpecIssue::node(
+                    &node.id,
+                    Code::SpecParentInvalid,
+                    vec![("parent".to_string(), parent_id.clone())],
+                ));
+            }
+        }
+    }
+
+    fn lint_edges(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        for edge in &self.edges {
+            let Some(source) = graph.node(&edge.source) else {
+                issues.push(SpecIssue::edge(
+                    &edge.id,
+                    Code::SpecEdgeSourceMissing,
+                    vec![("source".to_string(), edge.source.clone())],
+                ));
+                continue;
+            };
+
+            if graph.node(&edge.target).is_none() {
+                issues.push(SpecIssue::edge(
+                    &edge.id,
+                    Code::SpecEdgeTargetMissing,
+                    vec![("target".to_string(), edge.target.clone())],
+                ));
+            }
+
+            // 条件分支必须从某个分支出口连出，其他节点只有一个默认出口
+            let handle_ok = match (&source.kind, edge.source_handle.as_deref()) {
+                (NodeKind::Case(case), Some(handle)) => {
+                    case.handle_ids().iter().any(|h| h == handle)
+                }
+                (NodeKind::Case(_), None) => false,
+                (_, handle) => handle.is_none(),
+            };
+            if !handle_ok {
+                issues.push(SpecIssue::edge(
+                    &edge.id,
+                    Code::SpecEdgeHandleMissing,
+                    vec![
+                        ("source".to_string(), edge.source.clone()),
+                        (
+                            "handle".to_string(),
+                            edge.source_handle.clone().unwrap_or_default(),
+                        ),
+                    ],
+                ));
+            }
+        }
+    }
+
+    fn lint_reachability(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        let Some(start) = graph.start() else {
+            return;
+        };
+
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.id.as_str()) {
+                continue;
+            }
+            stack.extend(graph.next_all(&node.id));
+            if node.kind.is_group() {
+                stack.extend(graph.group_entries(&node.id));
+            }
+        }
+
+        let end_reachable = self
+            .nodes
+            .iter()
+            .any(|n| matches!(n.kind, NodeKind::End(_)) && visited.contains(n.id.as_str()));
+        if !end_reachable {
+            issues.push(SpecIssue::spec(Code::SpecEndUnreachable, vec![]));
+        }
+
+        for node in &self.nodes {
+            if visited.contains(node.id.as_str())
+                || matches!(node.kind, NodeKind::Start(_) | NodeKind::Note(_))
+            {
+                continue;
+            }
+            // 所在容器本身不可达时只提示容器，避免重复
+            let parent_reachable = node
+                .parent_id
+                .as_deref()
+                .is_none_or(|p| visited.contains(p));
+            if parent_reachable {
+                issues.push(SpecIssue::node(&node.id, Code::SpecNodeOrphan, vec![]));
+            }
+        }
+    }
+
+    fn lint_cycles(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        // 0-未访问，1-访问中，2-已完成
+        let mut state: HashMap<&str, u8> = HashMap::new();
+        let mut reported: HashSet<&str> = HashSet::new();
+
+        for node in &self.nodes {
+            if state.contains_key(node.id.as_str()) {
+                continue;
+            }
+
+            // 迭代 DFS，path 记录当前路径用于还原环上的节点
+            let mut path: Vec<&str> = Vec::new();
+            let mut stack: Vec<(&str, usize)> = vec![(node.id.as_str(), 0)];
+            while let Some((id, next)) = stack.pop() {
+                if next == 0 {
+                    state.insert(id, 1);
+                    path.push(id);
+                }
+
+                let edges = graph.outgoing(id);
+                if next < edges.len() {
+                    stack.push((id, next + 1));
+                    let target = edges[next].target.as_str();
+                    if graph.node(target).is_none() {
+                        continue;
+                    }
+                    match state.get(target).copied().unwrap_or(0) {
+                        0 => stack.push((target, 0)),
+                        1 => {
+                            let from = path.iter().position(|p| *p == target).unwrap_or(0);
+                            let cycle = &path[from..];
+                            let in_loop = cycle.iter().all(|id| graph.enclosing_loop(id).is_some());
+                            if !in_loop {
+                                for id in cycle {
+                                    if reported.insert(id) {
+                                        issues.push(SpecIssue::node(id, Code::SpecCycle, vec![]));
+                                    }
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                } else {
+                    state.insert(id, 2);
+                    path.pop();
+                }
+            }
+        }
+    }
+
+    fn lint_loop_controls(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        for node in &self.nodes {
+            if matches!(node.kind, NodeKind::LoopBreak | NodeKind::LoopContinue)
+                && graph.enclosing_loop(&node.id).is_none()
+            {
+                issues.push(SpecIssue::node(
+                    &node.id,
+                    Code::SpecLoopControlOutside,
+                    vec![],
+                ));
+            }
+        }
+    }
+
+    // 引用的变量必须在节点处可见，重复的引用只提示一次
+    fn lint_references(&self, graph: &SpecGraph, issues: &mut Vec<SpecIssue>) {
+        for node in &self.nodes {
+            let refs = node.kind.references();
+            if refs.is_empty() {
+                continue;
+            }
+            let visible: HashSet<String> = graph
+                .visible(&node.id)
+                .into_iter()
+                .map(|v| v.var_id)
+                .collect();
+            let mut reported = HashSet::new();
+            for reference in refs {
+                if !visible.contains(&reference) && reported.insert(reference.clone()) {
+                    issues.push(SpecIssue::node(
+                        &node.id,
+                        Code::SpecVariableUnavailable,
+                        vec![("var".to_string(), reference)],
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_codes(spec: &str) -> Vec<(Option<String>, i32)> {
+        AppSpec::parse(spec)
+            .unwrap()
+            .lint()
+            .into_iter()
+            .map(|i| (i.node_id.or(i.edge_id), i.code))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_valid_spec() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {}},
+                {"id": "c", "type": "case", "data": {"cases": [{"id": "k", "conditions": []}]}},
+                {"id": "l", "type": "loop", "data": {"type": "forever"}},
+                {"id": "g", "type": "group-start", "parentId": "l", "data": {}},
+                {"id": "b", "type": "loop-break", "parentId": "l", "data": {}},
+                {"id": "e", "type": "end", "data": {}},
+                {"id": "n", "type": "note", "data": {"content": "todo"}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "c"},
+                {"id": "2", "source": "c", "sourceHandle": "if-k", "target": "l"},
+                {"id": "3", "source": "c", "sourceHandle": "else", "target": "e"},
+                {"id": "4", "source": "g", "target": "b"},
+                {"id": "5", "source": "l", "target": "e"}
+            ]
+        }"#;
+        assert!(lint_codes(spec).is_empty());
+    }
+
+    #[test]
+    fn test_lint_issues() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {}},
+                {"id": "a", "type": "code", "data": {}},
+                {"id": "b", "type": "code", "data": {}},
+                {"id": "c", "type": "case", "data": {"cases": [{"id": "k", "conditions": []}]}},
+                {"id": "o", "type": "code", "data": {}},
+                {"id": "x", "type": "loop-continue", "data": {}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "a"},
+                {"id": "2", "source": "a", "target": "b"},
+                {"id": "3", "source": "b", "target": "a"},
+                {"id": "4", "source": "b", "target": "c"},
+                {"id": "5", "source": "c", "sourceHandle": "elif-z", "target": "m"},
+                {"id": "6", "source": "x", "target": "o"}
+            ]
+        }"#;
+
+        let issues = lint_codes(spec);
+        let has = |id: &str, code: Code| issues.contains(&(Some(id.to_string()), code.into()));
+        assert!(issues.contains(&(None, Code::SpecEndUnreachable.into())));
+        assert!(has("5", Code::SpecEdgeTargetMissing));
+        assert!(has("5", Code::SpecEdgeHandleMissing));
+        assert!(has("o", Code::SpecNodeOrphan));
+        assert!(has("x", Code::SpecNodeOrphan));
+        assert!(has("a", Code::SpecCycle));
+        assert!(has("b", Code::SpecCycle));
+        assert!(has("x", Code::SpecLoopControlOutside));
+        assert!(!has("s", Code::SpecNodeOrphan));
+    }
+
+    #[test]
+    fn test_lint_start() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "l", "type": "loop", "data": {"type": "forever"}},
+                {"id": "s", "type": "start", "parentId": "l", "data": {}}
+            ],
+            "edges": []
+        }"#;
+        let issues = lint_codes(spec);
+        assert!(issues.contains(&(Some("s".to_string()), Code::SpecStartNested.into())));
+        assert!(issues.contains(&(None, Code::SpecStartMissing.into())));
+        assert!(!issues.contains(&(Some("s".to_string()), Code::SpecStartMultiple.into())));
+    }
+
+    #[test]
+    fn test_lint_references() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"input": [{"id": "v", "name": "age"}]}},
+                {"id": "c", "type": "case", "data": {"cases": [{"id": "k",
+                 "conditions": [{"varId": "v", "opr": "="}, {"varId": "o", "opr": "="}]}]}},
+                {"id": "a", "type": "code", "data": {"input": [{"name": "x", "value": "o"}],
+                 "output": [{"id": "o", "name": "y"}]}},
+                {"id": "x", "type": "assign", "data": {"items": [
+                    {"target": "o", "source": {"type": "expression", "value": "{{o}} + {{v}}"}},
+                    {"scope": "conversation", "target": "missing", "source": {"type": "reference", "value": "b"}}
+                ]}},
+                {"id": "b", "type": "code", "data": {"output": [{"id": "b", "name": "z"}]}},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [{"name": "r", "value": "o"}]}}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "c"},
+                {"id": "2", "source": "c", "sourceHandle": "if-k", "target": "a"},
+                {"id": "3", "source": "c", "sourceHandle": "else", "target": "b"},
+                {"id": "4", "source": "a", "target": "x"},
+                {"id": "5", "source": "x", "target": "e"},
+                {"id": "6", "source": "b", "target": "e"}
+            ]
+        }"#;
+
+        let issues: Vec<_> = lint_codes(spec)
+            .into_iter()
+            .filter(|(_, code)| *code == i32::from(Code::SpecVariableUnavailable))
+            .map(|(id, _)| id.unwrap_or_default())
+            .collect();
+        // 条件引用下游输出、代码节点引用自身输出、赋值的会话变量未声明且引用旁路分支
+        assert_eq!(issues, vec!["c", "a", "x", "x"]);
+    }
+}
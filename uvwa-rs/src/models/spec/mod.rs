@@ -0,0 +1,269 @@
+pub mod diff;
+pub mod graph;
+pub mod lint;
+pub mod node;
+pub mod scope;
+pub mod token;
+pub mod variable;
+
+use crate::core::code::Code;
+use crate::models::spec::node::NodeKind;
+use crate::models::spec::variable::Variable;
+use crate::web::r::translate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// 应用草稿：编辑器保存的 nodes/edges/viewport 文档
+#[derive(Debug, Clone)]
+pub struct AppSpec {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    pub viewport: Option<Viewport>,
+    // 对话应用的会话变量声明，按变量名读写
+    pub conversation_variables: Vec<Variable>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: String,
+    // 所属容器节点（循环、SQL 事务）
+    pub parent_id: Option<String>,
+    pub kind: NodeKind,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub source_handle: Option<String>,
+    pub target_handle: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default)]
+pub struct Viewport {
+    pub x: f64,
+    pub y: f64,
+    pub zoom: f64,
+}
+
+// 草稿校验问题，nodeId/edgeId 供编辑器定位高亮
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge_id: Option<String>,
+    pub code: i32,
+    pub message: String,
+}
+
+impl SpecIssue {
+    pub fn spec(code: Code, args: Vec<(String, String)>) -> Self {
+        Self::new(None, None, code, args)
+    }
+
+    pub fn node(node_id: &str, code: Code, mut args: Vec<(String, String)>) -> Self {
+        args.push(("node".to_string(), node_id.to_string()));
+        Self::new(Some(node_id.to_string()), None, code, args)
+    }
+
+    pub fn edge(edge_id: &str, code: Code, mut args: Vec<(String, String)>) -> Self {
+        args.push(("edge".to_string(), edge_id.to_string()));
+        Self::new(None, Some(edge_id.to_string()), code, args)
+    }
+
+    fn new(
+        node_id: Option<String>,
+        edge_id: Option<String>,
+        code: Code,
+        args: Vec<(String, String)>,
+    ) -> Self {
+        let code: i32 = code.into();
+        Self {
+            node_id,
+            edge_id,
+            code,
+            message: translate(code, &args),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawSpec {
+    #[serde(default)]
+    nodes: Vec<serde_json::Value>,
+    #[serde(default)]
+    edges: Vec<serde_json::Value>,
+    viewport: Option<Viewport>,
+    #[serde(default, rename = "conversationVariables")]
+    conversation_variables: Vec<Variable>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawNode {
+    id: String,
+    #[serde(rename = "type")]
+    node_type: String,
+    parent_id: Option<String>,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+impl AppSpec {
+    // 解析草稿，逐个节点、连线收集错误，而不是在第一个错误处返回
+    pub fn parse(spec: &str) -> Result<Self, Vec<SpecIssue>> {
+        let raw: RawSpec = serde_json::from_str(spec)
+            .map_err(|e| vec![SpecIssue::spec(Code::SpecMalformed, detail(&e))])?;
+
+        let mut issues = Vec::new();
+        let mut ids = HashSet::new();
+        let mut nodes = Vec::with_capacity(raw.nodes.len());
+        for (index, value) in raw.nodes.into_iter().enumerate() {
+            let label = value_id(&value).unwrap_or_else(|| format!("#{}", index + 1));
+            let raw_node: RawNode = match serde_json::from_value(value) {
+                Ok(n) => n,
+                Err(e) => {
+                    issues.push(SpecIssue::node(&label, Code::SpecNodeInvalid, detail(&e)));
+                    continue;
+                }
+            };
+
+            if !ids.insert(raw_node.id.clone()) {
+                issues.push(SpecIssue::node(
+                    &raw_node.id,
+                    Code::SpecNodeDuplicate,
+                    vec![],
+                ));
+                continue;
+            }
+
+            match NodeKind::from_data(&raw_node.node_type, raw_node.data) {
+                Ok(Some(kind)) => nodes.push(Node {
+                    id: raw_node.id,
+                    parent_id: raw_node.parent_id.filter(|p| !p.is_empty()),
+                    kind,
+                }),
+                Ok(None) => issues.push(SpecIssue::node(
+                    &raw_node.id,
+                    Code::SpecNodeTypeUnknown,
+                    vec![("type".to_string(), raw_node.node_type)],
+                )),
+                Err(e) => issues.push(SpecIssue::node(
+                    &raw_node.id,
+                    Code::SpecNodeInvalid,
+                    detail(&e),
+                )),
+            }
+        }
+
+        let mut edges = Vec::with_capacity(raw.edges.len());
+        for (index, value) in raw.edges.into_iter().enumerate() {
+            let label = value_id(&value).unwrap_or_else(|| format!("#{}", index + 1));
+            match serde_json::from_value::<Edge>(value) {
+                Ok(edge) => edges.push(edge),
+                Err(e) => issues.push(SpecIssue::edge(&label, Code::SpecEdgeInvalid, detail(&e))),
+            }
+        }
+
+        if !issues.is_empty() {
+            return Err(issues);
+        }
+
+        Ok(Self {
+            nodes,
+            edges,
+            viewport: raw.viewport,
+            conversation_variables: raw.conversation_variables,
+        })
+    }
+
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+}
+
+fn value_id(value: &serde_json::Value) -> Option<String> {
+    value
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+fn detail(e: &serde_json::Error) -> Vec<(String, String)> {
+    vec![("detail".to_string(), e.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::spec::node::{CompareOpr, LoopType};
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "position": {"x": 0, "y": 0},
+                 "data": {"title": "开始", "input": [{"id": "v1", "name": "age", "type": "INTEGER",
+                          "rules": [{"type": "required"}, {"type": "max", "value": "20"}]}]}},
+                {"id": "c", "type": "case",
+                 "data": {"cases": [{"id": "k1", "opr": "and",
+                          "conditions": [{"varId": "v1", "opr": ">=", "value": "18"}]}]}},
+                {"id": "l", "type": "loop", "data": {"type": "while", "whileNumber": "3"}},
+                {"id": "g", "type": "group-start", "parentId": "l", "data": {"group": false}},
+                {"id": "e", "type": "end", "data": {"output": {"vars": [], "rCode": "0"}}}
+            ],
+            "edges": [{"id": "e1", "source": "s", "target": "c", "sourceHandle": null}],
+            "viewport": {"x": 0, "y": 0, "zoom": 1}
+        }"#;
+
+        let spec = AppSpec::parse(spec).unwrap();
+        assert_eq!(spec.nodes.len(), 5);
+        assert_eq!(spec.edges.len(), 1);
+
+        let NodeKind::Case(case) = &spec.node("c").unwrap().kind else {
+            panic!("expect case node");
+        };
+        assert_eq!(case.cases[0].conditions[0].opr, CompareOpr::Ge);
+
+        let NodeKind::Loop(lp) = &spec.node("l").unwrap().kind else {
+            panic!("expect loop node");
+        };
+        assert_eq!(lp.loop_type, Some(LoopType::While));
+        assert_eq!(lp.while_number, Some(3));
+        assert_eq!(spec.node("g").unwrap().parent_id.as_deref(), Some("l"));
+    }
+
+    #[test]
+    fn test_parse_spec_errors() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "a", "type": "start", "data": {"input": [{"id": "v", "name": "x", "type": "NUMBER"}]}},
+                {"id": "a", "type": "end", "data": {}},
+                {"id": "b", "type": "unknown", "data": {}},
+                {"type": "code", "data": {}}
+            ],
+            "edges": [{"id": "e1", "source": "a"}]
+        }"#;
+
+        let issues = AppSpec::parse(spec).unwrap_err();
+        let codes: Vec<i32> = issues.iter().map(|i| i.code).collect();
+        let expected: Vec<i32> = vec![
+            Code::SpecNodeInvalid.into(),
+            Code::SpecNodeDuplicate.into(),
+            Code::SpecNodeTypeUnknown.into(),
+            Code::SpecNodeInvalid.into(),
+            Code::SpecEdgeInvalid.into(),
+        ];
+        assert_eq!(codes, expected);
+        assert_eq!(issues[0].node_id.as_deref(), Some("a"));
+        assert_eq!(issues[3].node_id.as_deref(), Some("#4"));
+        assert_eq!(issues[4].edge_id.as_deref(), Some("e1"));
+
+        let issues = AppSpec::parse("not json").unwrap_err();
+        assert_eq!(issues[0].code, i32::from(Code::SpecMalformed));
+    }
+}
@@ -0,0 +1,407 @@
+use crate::models::spec::variable::Variable;
+use crate::web::ts_str::{option_non_empty, option_str_or_number};
+use serde::{Deserialize, Serialize};
+
+// 节点类型 key，与编辑器 nodeTypes.ts 中的 NODE_TYPE 保持一致
+pub const NODE_START: &str = "start";
+pub const NODE_END: &str = "end";
+pub const NODE_GROUP_START: &str = "group-start";
+pub const NODE_CASE: &str = "case";
+pub const NODE_LOOP: &str = "loop";
+pub const NODE_LOOP_CONTINUE: &str = "loop-continue";
+pub const NODE_LOOP_BREAK: &str = "loop-break";
+pub const NODE_CODE: &str = "code";
+pub const NODE_SQL: &str = "sql";
+pub const NODE_SQL_TRANSACTION: &str = "sql-transaction";
+pub const NODE_SUBFLOW: &str = "subflow";
+pub const NODE_ASSIGN: &str = "assign";
+pub const NODE_NOTE: &str = "note";
+
+// 条件分支 ELSE 出口的 handle id
+pub const HANDLE_ELSE: &str = "else";
+
+#[derive(Debug, Clone)]
+pub enum NodeKind {
+    Start(StartNode),
+    End(EndNode),
+    GroupStart,
+    Case(CaseNode),
+    Loop(LoopNode),
+    LoopContinue,
+    LoopBreak,
+    Code(CodeNode),
+    Sql(SqlNode),
+    SqlTransaction(SqlTransactionNode),
+    SubFlow(SubFlowNode),
+    Assign(AssignNode),
+    Note(NoteNode),
+}
+
+impl NodeKind {
+    // 根据节点类型 key 解析节点数据，类型未知时返回 Ok(None)
+    pub fn from_data(
+        node_type: &str,
+        data: serde_json::Value,
+    ) -> Result<Option<Self>, serde_json::Error> {
+        let kind = match node_type {
+            NODE_START => Self::Start(serde_json::from_value(data)?),
+            NODE_END => Self::End(serde_json::from_value(data)?),
+            NODE_GROUP_START => Self::GroupStart,
+            NODE_CASE => Self::Case(serde_json::from_value(data)?),
+            NODE_LOOP => Self::Loop(serde_json::from_value(data)?),
+            NODE_LOOP_CONTINUE => Self::LoopContinue,
+            NODE_LOOP_BREAK => Self::LoopBreak,
+            NODE_CODE => Self::Code(serde_json::from_value(data)?),
+            NODE_SQL => Self::Sql(serde_json::from_value(data)?),
+            NODE_SQL_TRANSACTION => Self::SqlTransaction(serde_json::from_value(data)?),
+            NODE_SUBFLOW => Self::SubFlow(serde_json::from_value(data)?),
+            NODE_ASSIGN => Self::Assign(serde_json::from_value(data)?),
+            NODE_NOTE => Self::Note(serde_json::from_value(data)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(kind))
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Start(_) => NODE_START,
+            Self::End(_) => NODE_END,
+            Self::GroupStart => NODE_GROUP_START,
+            Self::Case(_) => NODE_CASE,
+            Self::Loop(_) => NODE_LOOP,
+            Self::LoopContinue => NODE_LOOP_CONTINUE,
+            Self::LoopBreak => NODE_LOOP_BREAK,
+            Self::Code(_) => NODE_CODE,
+            Self::Sql(_) => NODE_SQL,
+            Self::SqlTransaction(_) => NODE_SQL_TRANSACTION,
+            Self::SubFlow(_) => NODE_SUBFLOW,
+            Self::Assign(_) => NODE_ASSIGN,
+            Self::Note(_) => NODE_NOTE,
+        }
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        let title = match self {
+            Self::Start(n) => &n.title,
+            Self::End(n) => &n.title,
+            Self::Case(n) => &n.title,
+            Self::Loop(n) => &n.title,
+            Self::Code(n) => &n.title,
+            Self::Sql(n) => &n.title,
+            Self::SqlTransaction(n) => &n.title,
+            Self::SubFlow(n) => &n.title,
+            Self::Assign(n) => &n.title,
+            Self::Note(n) => &n.title,
+            Self::GroupStart | Self::LoopContinue | Self::LoopBreak => return None,
+        };
+        title.as_deref().filter(|t| !t.is_empty())
+    }
+
+    // 容器节点：其内部节点通过 parentId 指向它
+    pub fn is_group(&self) -> bool {
+        matches!(self, Self::Loop(_) | Self::SqlTransaction(_))
+    }
+
+    // 节点声明的变量：开始节点的输入、各节点的输出
+    pub fn variables(&self) -> &[Variable] {
+        match self {
+            Self::Start(start) => &start.input,
+            Self::Code(code) => &code.output,
+            Self::Sql(sql) => &sql.output,
+            Self::SubFlow(sub) => &sub.output,
+            _ => &[],
+        }
+    }
+}
+
+// 开始节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StartNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub input: Vec<Variable>,
+}
+
+// 结束节点输出配置
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EndNodeOutput {
+    #[serde(default)]
+    pub vars: Vec<Variable>,
+    #[serde(default)]
+    pub is_wrap: bool,
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub r_code: Option<i32>,
+    pub r_message: Option<String>,
+    #[serde(default)]
+    pub is_text: bool,
+    #[serde(default)]
+    pub is_stream: bool,
+    pub text: Option<String>,
+}
+
+// 结束节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EndNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub output: Option<EndNodeOutput>,
+}
+
+// 条件比较操作符，in/not in 为包含/不包含（沿用已保存流程的含义），one of/not one of 为属于/不属于
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOpr {
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "not in")]
+    NotIn,
+    #[serde(rename = "one of")]
+    OneOf,
+    #[serde(rename = "not one of")]
+    NotOneOf,
+    #[serde(rename = "=")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = "start with")]
+    StartWith,
+    #[serde(rename = "end with")]
+    EndWith,
+    #[serde(rename = "is empty")]
+    IsEmpty,
+    #[serde(rename = "is not empty")]
+    IsNotEmpty,
+    #[serde(rename = "regex")]
+    Regex,
+}
+
+// 条件组合方式
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogicOpr {
+    #[default]
+    And,
+    Or,
+}
+
+// 条件分支 - 条件
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Condition {
+    pub var_id: Option<String>,
+    pub opr: CompareOpr,
+    #[serde(default)]
+    pub value: String,
+}
+
+// 条件分支 - 分支
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Case {
+    pub id: String,
+    #[serde(default)]
+    pub opr: LogicOpr,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+}
+
+// 条件分支节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub cases: Vec<Case>,
+}
+
+impl CaseNode {
+    // 分支出口 handle id：第一个分支为 if-{id}，其余为 elif-{id}，与编辑器保持一致
+    pub fn handle_ids(&self) -> Vec<String> {
+        let mut handles: Vec<String> = self
+            .cases
+            .iter()
+            .enumerate()
+            .map(|(i, c)| Self::case_handle(i, &c.id))
+            .collect();
+        handles.push(HANDLE_ELSE.to_string());
+        handles
+    }
+
+    pub fn case_handle(index: usize, case_id: &str) -> String {
+        if index == 0 {
+            format!("if-{}", case_id)
+        } else {
+            format!("elif-{}", case_id)
+        }
+    }
+}
+
+// 循环类型
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LoopType {
+    For,
+    While,
+    Forever,
+}
+
+// 循环节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(rename = "type", default, deserialize_with = "option_non_empty")]
+    pub loop_type: Option<LoopType>,
+    pub for_var_id: Option<String>,
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub while_number: Option<u64>,
+    pub body_var_name: Option<String>,
+    pub body_index_name: Option<String>,
+}
+
+// 脚本语言
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptLanguage {
+    #[default]
+    Javascript,
+    Python,
+}
+
+// 代码节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub language: ScriptLanguage,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub input: Vec<Variable>,
+    #[serde(default)]
+    pub output: Vec<Variable>,
+    // 超时时间（秒）
+    #[serde(default, deserialize_with = "option_str_or_number")]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub debug: bool,
+}
+
+// SQL 节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub conn_key: Option<String>,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub input: Vec<Variable>,
+    #[serde(default)]
+    pub output: Vec<Variable>,
+}
+
+// SQL 事务节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SqlTransactionNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub conn_key: Option<String>,
+}
+
+// 子流程节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubFlowNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    // 引用的应用 id
+    pub flow_id: Option<String>,
+    // 指定的版本号，为空时使用最新发布的版本
+    pub version: Option<String>,
+    #[serde(default)]
+    pub input: Vec<Variable>,
+    #[serde(default)]
+    pub output: Vec<Variable>,
+}
+
+// 变量赋值节点：依次执行各赋值项
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub items: Vec<AssignItem>,
+}
+
+// 赋值目标所在的作用域
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AssignScope {
+    // 流程变量：开始节点的输入和各节点的输出
+    #[default]
+    Workflow,
+    // 会话变量：对话应用在多轮对话间保留的变量
+    Conversation,
+}
+
+// 赋值方式
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AssignMode {
+    // 覆盖原值
+    #[default]
+    Set,
+    // 追加到数组末尾，值为数组时逐个追加
+    Append,
+}
+
+// 赋值来源：字面量、变量引用或表达式
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum AssignSource {
+    Literal(serde_json::Value),
+    // 引用的变量 id
+    Reference(String),
+    Expression(String),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignItem {
+    #[serde(default)]
+    pub scope: AssignScope,
+    // 流程变量为变量 id，会话变量为变量名
+    pub target: String,
+    #[serde(default)]
+    pub mode: AssignMode,
+    pub source: AssignSource,
+}
+
+// 注释节点
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteNode {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub content: String,
+}
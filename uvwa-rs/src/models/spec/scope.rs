@@ -0,0 +1,253 @@
+use crate::models::spec::Node;
+use crate::models::spec::graph::SpecGraph;
+use crate::models::spec::node::{AssignScope, AssignSource, LoopType, NodeKind};
+use crate::models::spec::token;
+use crate::models::spec::variable::{
+    Variable, VariableKind, conversation_key, loop_index_key, loop_item_key, var_key,
+};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+// 节点可引用的变量，varId 即引用时填写的变量表 key
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeVar {
+    pub var_id: String,
+    pub var_name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<VariableKind>,
+    // 声明变量的节点，会话变量没有所属节点
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_name: Option<String>,
+}
+
+impl ScopeVar {
+    fn declared(node: &Node, var: &Variable) -> Self {
+        Self::of_node(node, var_key(&node.id, var), &var.name, var.kind)
+    }
+
+    fn of_node(node: &Node, var_id: String, name: &str, kind: Option<VariableKind>) -> Self {
+        Self {
+            var_id,
+            var_name: name.to_string(),
+            kind,
+            node_id: Some(node.id.clone()),
+            node_type: Some(node.kind.type_name()),
+            node_name: node.kind.title().map(|t| t.to_string()),
+        }
+    }
+}
+
+impl<'a> SpecGraph<'a> {
+    // 节点可引用的变量：开始节点的输入，沿入边向上的各节点（含上游容器内的节点）的输出，
+    // 所在循环的循环项/下标，所在容器的上游同样可见；最后是会话变量。
+    // 上游节点由近到远排列，编辑器的变量选择和草稿校验、运行时使用同一结果
+    pub fn visible(&self, id: &str) -> Vec<ScopeVar> {
+        let mut vars = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::from([id]);
+
+        // 开始节点总是最先执行，不要求有连线到达
+        if let Some(start) = self.start().filter(|s| seen.insert(&s.id)) {
+            self.declared_within(start, &mut seen, &mut vars);
+        }
+
+        let mut current = self.node(id);
+        while let Some(node) = current {
+            let mut queue = VecDeque::from([node.id.as_str()]);
+            while let Some(id) = queue.pop_front() {
+                for edge in self.incoming(id) {
+                    let Some(source) = self.node(&edge.source) else {
+                        continue;
+                    };
+                    if seen.insert(&source.id) {
+                        self.declared_within(source, &mut seen, &mut vars);
+                        queue.push_back(&source.id);
+                    }
+                }
+            }
+
+            // parentId 成环时已访问过，停止向上
+            current = node
+                .parent_id
+                .as_deref()
+                .and_then(|p| self.node(p))
+                .filter(|p| seen.insert(&p.id));
+            if let Some(parent) = current
+                && let NodeKind::Loop(lp) = &parent.kind
+            {
+                // for 循环的循环项为数组元素，while/forever 的循环项与下标相同
+                let item_kind = match lp.loop_type {
+                    Some(LoopType::For) => lp
+                        .for_var_id
+                        .as_deref()
+                        .and_then(|key| self.variable(key))
+                        .and_then(|v| v.kind)
+                        .and_then(|k| k.element()),
+                    _ => Some(VariableKind::Integer),
+                };
+                let item = lp.body_var_name.as_deref().filter(|n| !n.is_empty());
+                let index = lp.body_index_name.as_deref().filter(|n| !n.is_empty());
+                vars.push(ScopeVar::of_node(
+                    parent,
+                    loop_item_key(&parent.id, item),
+                    item.unwrap_or("item"),
+                    item_kind,
+                ));
+                vars.push(ScopeVar::of_node(
+                    parent,
+                    loop_index_key(&parent.id, index),
+                    index.unwrap_or("index"),
+                    Some(VariableKind::Integer),
+                ));
+            }
+        }
+
+        vars.extend(self.conversation().iter().map(|var| ScopeVar {
+            var_id: conversation_key(&var.name),
+            var_name: var.name.clone(),
+            kind: var.kind,
+            node_id: None,
+            node_type: None,
+            node_name: None,
+        }));
+        vars
+    }
+
+    // 节点声明的变量；容器执行完后其内部节点的输出同样保留在变量表中
+    fn declared_within(
+        &self,
+        node: &'a Node,
+        seen: &mut HashSet<&'a str>,
+        vars: &mut Vec<ScopeVar>,
+    ) {
+        vars.extend(
+            node.kind
+                .variables()
+                .iter()
+                .map(|v| ScopeVar::declared(node, v)),
+        );
+        for child in self.children(&node.id) {
+            if seen.insert(&child.id) {
+                self.declared_within(child, seen, vars);
+            }
+        }
+    }
+}
+
+impl NodeKind {
+    // 节点引用的变量 key：条件变量、循环数组、结束节点输出、各节点输入的取值，
+    // 以及赋值节点的目标、引用和表达式中的变量
+    pub fn references(&self) -> Vec<String> {
+        let inputs = |vars: &[Variable]| -> Vec<String> {
+            vars.iter()
+                .filter_map(|v| v.value.clone())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+        let mut refs = match self {
+            Self::Case(case) => case
+                .cases
+                .iter()
+                .flat_map(|c| &c.conditions)
+                .filter_map(|c| c.var_id.clone())
+                .collect(),
+            Self::Loop(lp) if lp.loop_type == Some(LoopType::For) => {
+                lp.for_var_id.clone().into_iter().collect()
+            }
+            Self::End(end) => end
+                .output
+                .as_ref()
+                .map(|o| inputs(&o.vars))
+                .unwrap_or_default(),
+            Self::Code(code) => inputs(&code.input),
+            Self::Sql(sql) => inputs(&sql.input),
+            Self::SubFlow(sub) => inputs(&sub.input),
+            Self::Assign(assign) => {
+                let mut refs = Vec::new();
+                for item in &assign.items {
+                    refs.push(match item.scope {
+                        AssignScope::Workflow => item.target.clone(),
+                        AssignScope::Conversation => conversation_key(&item.target),
+                    });
+                    match &item.source {
+                        AssignSource::Literal(_) => {}
+                        AssignSource::Reference(reference) => refs.push(reference.clone()),
+                        // 语法错误在运行时报告，这里只检查能解析出的引用
+                        AssignSource::Expression(expression) => {
+                            refs.extend(token::references(expression).unwrap_or_default())
+                        }
+                    }
+                }
+                refs
+            }
+            _ => Vec::new(),
+        };
+        refs.retain(|r| !r.is_empty());
+        refs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::spec::AppSpec;
+
+    #[test]
+    fn test_visible() {
+        let spec = r#"{
+            "nodes": [
+                {"id": "s", "type": "start", "data": {"title": "开始",
+                 "input": [{"id": "items", "name": "items", "type": "ARRAY_STRING"}]}},
+                {"id": "a", "type": "code", "data": {"output": [{"id": "a1", "name": "x", "type": "INTEGER"}]}},
+                {"id": "l", "type": "loop", "data": {"type": "for", "forVarId": "items", "bodyVarName": "name"}},
+                {"id": "g", "type": "group-start", "parentId": "l", "data": {}},
+                {"id": "b", "type": "code", "parentId": "l", "data": {"output": [{"id": "b1", "name": "y"}]}},
+                {"id": "c", "type": "code", "parentId": "l", "data": {"output": [{"id": "c1", "name": "z"}]}},
+                {"id": "d", "type": "code", "data": {"output": [{"id": "d1", "name": "w"}]}},
+                {"id": "e", "type": "end", "data": {}}
+            ],
+            "edges": [
+                {"id": "1", "source": "s", "target": "a"},
+                {"id": "2", "source": "a", "target": "l"},
+                {"id": "3", "source": "g", "target": "b"},
+                {"id": "4", "source": "b", "target": "c"},
+                {"id": "5", "source": "l", "target": "e"},
+                {"id": "6", "source": "s", "target": "d"}
+            ],
+            "conversationVariables": [{"id": "", "name": "turns", "type": "INTEGER"}]
+        }"#;
+        let spec = AppSpec::parse(spec).unwrap();
+        let graph = SpecGraph::new(&spec);
+        let ids =
+            |id: &str| -> Vec<String> { graph.visible(id).into_iter().map(|v| v.var_id).collect() };
+
+        // 循环体内：同一循环体的上游、循环项/下标、循环节点的上游
+        assert_eq!(
+            ids("c"),
+            vec![
+                "items",
+                "b1",
+                "l.name",
+                "l.index",
+                "a1",
+                "conversation.turns"
+            ]
+        );
+        // 循环之后：循环体内节点的输出仍可引用，旁路分支 d 不可见
+        assert_eq!(
+            ids("e"),
+            vec!["items", "b1", "c1", "a1", "conversation.turns"]
+        );
+        assert_eq!(ids("s"), vec!["conversation.turns"]);
+
+        let vars = graph.visible("c");
+        assert_eq!(vars[0].node_name.as_deref(), Some("开始"));
+        assert_eq!(vars[2].var_name, "name");
+        assert_eq!(vars[2].kind, Some(VariableKind::String));
+        assert_eq!(vars[2].node_type, Some("loop"));
+    }
+}
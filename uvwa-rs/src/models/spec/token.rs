@@ -0,0 +1,127 @@
+use serde_json::{Number, Value};
+use std::fmt;
+
+// 表达式的词法分析，引擎求值和草稿检查共用
+// 表达式中的变量引用，按出现顺序
+pub fn references(expr: &str) -> Result<Vec<String>, String> {
+    let refs = tokenize(expr)?
+        .into_iter()
+        .filter_map(|t| match t {
+            Token::Ref(reference) => Some(reference),
+            _ => None,
+        })
+        .collect();
+    Ok(refs)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Lit(Value),
+    Ref(String),
+    Ident(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lit(v) => write!(f, "{}", v),
+            Self::Ref(r) => write!(f, "{{{{{}}}}}", r),
+            Self::Ident(i) => write!(f, "{}", i),
+            Self::Op(op) => write!(f, "'{}'", op),
+        }
+    }
+}
+
+// 多字符的操作符在前，保证最长匹配
+const OPS: [&str; 20] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "+", "-", "*", "/", "%", "!", "(", ")", "[", "]",
+    ",", ".",
+];
+
+pub fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap_or_default();
+        let len = if c.is_ascii_digit() {
+            let len = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(rest.len());
+            let text = &rest[..len];
+            let value = match text.parse::<i64>() {
+                Ok(n) => Value::from(n),
+                Err(_) => text
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+                    .ok_or_else(|| format!("invalid number {}", text))?,
+            };
+            tokens.push(Token::Lit(value));
+            len
+        } else if c == '\'' || c == '"' {
+            let (text, len) = string(rest, c)?;
+            tokens.push(Token::Lit(Value::String(text)));
+            len
+        } else if let Some(body) = rest.strip_prefix("{{") {
+            let end = body.find("}}").ok_or("unclosed variable reference")?;
+            tokens.push(Token::Ref(body[..end].trim().to_string()));
+            end + 4
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(match &rest[..len] {
+                "true" => Token::Lit(Value::Bool(true)),
+                "false" => Token::Lit(Value::Bool(false)),
+                "null" => Token::Lit(Value::Null),
+                "and" => Token::Op("&&"),
+                "or" => Token::Op("||"),
+                "not" => Token::Op("!"),
+                ident => Token::Ident(ident.to_string()),
+            });
+            len
+        } else {
+            let op = OPS
+                .iter()
+                .find(|op| rest.starts_with(**op))
+                .ok_or_else(|| format!("unexpected character {}", c))?;
+            tokens.push(Token::Op(op));
+            op.len()
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+// 引号包围的字符串，支持 \n \t \\ 和引号转义，返回内容和占用的长度
+fn string(text: &str, quote: char) -> Result<(String, usize), String> {
+    let mut out = String::new();
+    let mut chars = text.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, 't')) => out.push('\t'),
+                Some((_, c)) => out.push(c),
+                None => break,
+            },
+            c if c == quote => return Ok((out, i + 1)),
+            c => out.push(c),
+        }
+    }
+    Err("unclosed string".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_references() {
+        let refs = references("{{ a }} + len({{b}}.items) > '{{c}}'").unwrap();
+        assert_eq!(refs, vec!["a", "b"]);
+        assert!(references("{{a").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+
+// 变量数据类型，与编辑器中的 VariableKind 保持一致
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VariableKind {
+    String,
+    Integer,
+    Long,
+    Decimal,
+    Boolean,
+    Object,
+    File,
+    FileImage,
+    FileVideo,
+    FileAudio,
+    FileDoc,
+    FileOther,
+    Array,
+    ArrayString,
+    ArrayInteger,
+    ArrayLong,
+    ArrayDecimal,
+    ArrayBoolean,
+    ArrayObject,
+    ArrayFileImage,
+    ArrayFileVideo,
+    ArrayFileAudio,
+    ArrayFileDoc,
+    ArrayFileOther,
+}
+
+impl VariableKind {
+    pub fn is_array(&self) -> bool {
+        matches!(
+            self,
+            Self::Array
+                | Self::ArrayString
+                | Self::ArrayInteger
+                | Self::ArrayLong
+                | Self::ArrayDecimal
+                | Self::ArrayBoolean
+                | Self::ArrayObject
+                | Self::ArrayFileImage
+                | Self::ArrayFileVideo
+                | Self::ArrayFileAudio
+                | Self::ArrayFileDoc
+                | Self::ArrayFileOther
+        )
+    }
+
+    pub fn is_file(&self) -> bool {
+        matches!(
+            self,
+            Self::File
+                | Self::FileImage
+                | Self::FileVideo
+                | Self::FileAudio
+                | Self::FileDoc
+                | Self::FileOther
+        )
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Integer | Self::Long | Self::Decimal)
+    }
+
+    // 数组的元素类型，非数组或无类型数组返回 None
+    pub fn element(&self) -> Option<VariableKind> {
+        match self {
+            Self::ArrayString => Some(Self::String),
+            Self::ArrayInteger => Some(Self::Integer),
+            Self::ArrayLong => Some(Self::Long),
+            Self::ArrayDecimal => Some(Self::Decimal),
+            Self::ArrayBoolean => Some(Self::Boolean),
+            Self::ArrayObject => Some(Self::Object),
+            Self::ArrayFileImage => Some(Self::FileImage),
+            Self::ArrayFileVideo => Some(Self::FileVideo),
+            Self::ArrayFileAudio => Some(Self::FileAudio),
+            Self::ArrayFileDoc => Some(Self::FileDoc),
+            Self::ArrayFileOther => Some(Self::FileOther),
+            _ => None,
+        }
+    }
+}
+
+// 与编辑器一致的类型名，例如 ARRAY_STRING
+impl std::fmt::Display for VariableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = serde_json::to_value(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", name.as_str().unwrap_or_default())
+    }
+}
+
+// 变量校验规则类型
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    Required,
+    Max,
+    Min,
+    Length,
+    Enum,
+    Size,
+    Email,
+    Pattern,
+}
+
+// 规则值：编辑器中可能是字符串、数字或布尔值
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum RuleValue {
+    Bool(bool),
+    Number(serde_json::Number),
+    Text(String),
+}
+
+impl RuleValue {
+    pub fn as_text(&self) -> String {
+        match self {
+            Self::Bool(b) => b.to_string(),
+            Self::Number(n) => n.to_string(),
+            Self::Text(s) => s.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    #[serde(rename = "type")]
+    pub kind: RuleKind,
+    pub value: Option<RuleValue>,
+    pub message: Option<String>,
+}
+
+// 变量定义
+// 开始节点的输入、各节点的输出都使用它；输入映射中 value 保存引用的上游变量 id
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Variable {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: Option<VariableKind>,
+    pub value: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+// 变量在变量表中的 key：优先使用变量 id，未设置 id 时使用「节点 id.变量名」
+pub fn var_key(node_id: &str, var: &Variable) -> String {
+    if var.id.is_empty() {
+        format!("{}.{}", node_id, var.name)
+    } else {
+        var.id.clone()
+    }
+}
+
+// 会话变量的 key：「conversation.变量名」
+pub fn conversation_key(name: &str) -> String {
+    format!("conversation.{}", name)
+}
+
+// 循环体变量的 key：「循环节点 id.变量名」，变量名默认为 item/index
+pub fn loop_item_key(node_id: &str, body_var_name: Option<&str>) -> String {
+    format!(
+        "{}.{}",
+        node_id,
+        body_var_name.filter(|n| !n.is_empty()).unwrap_or("item")
+    )
+}
+
+pub fn loop_index_key(node_id: &str, body_index_name: Option<&str>) -> String {
+    format!(
+        "{}.{}",
+        node_id,
+        body_index_name.filter(|n| !n.is_empty()).unwrap_or("index")
+    )
+}
@@ -1,4 +1,7 @@
+use crate::business::api_key::api_key_handler;
 use crate::business::app::app_handler;
+use crate::business::app_run::{debug_handler, publish_handler, run_handler};
+use crate::business::datasource::datasource_handler;
 use crate::business::user::user_handler;
 use crate::business::workspace::workspace_handler;
 use crate::business::workspace_folder::folder_handler;
@@ -19,9 +22,18 @@ pub fn router() -> Router {
         .nest("/uvwa/api", folder_routes())
         .nest("/uvwa/api", tag_routes())
         .nest("/uvwa/api", app_routes())
+        .nest("/uvwa/api", run_routes())
+        .nest("/uvwa/api", datasource_routes())
+        .nest("/uvwa/api", api_key_routes())
         .layer(middleware::from_fn(handle_auth));
 
-    Router::new().merge(public_routes).merge(protected_routes)
+    // 发布的接口，使用 API 密钥认证
+    let published_routes = Router::new().nest("/uvwa", published_routes());
+
+    Router::new()
+        .merge(public_routes)
+        .merge(protected_routes)
+        .merge(published_routes)
 }
 
 // user routes
@@ -97,4 +109,118 @@ fn app_routes() -> Router {
         .route("/apps/{id}/clone", post(app_handler::clone_app))
         .route("/apps/{id}/release", post(app_handler::release_app))
         .route("/apps/{id}/tags", put(app_handler::update_app_tags))
+        .route("/apps/{id}/versions", get(app_handler::list_app_versions))
+        .route(
+            "/apps/{id}/versions/{version}",
+            get(app_handler::get_app_version),
+        )
+        .route(
+            "/apps/{id}/versions/{version}/diff",
+            get(app_handler::diff_app_version),
+        )
+        .route(
+            "/apps/{id}/versions/{version}/restore",
+            post(app_handler::restore_app_version),
+        )
+        .route(
+            "/apps/{id}/versions/{version}/promote",
+            post(app_handler::promote_app_version),
+        )
+        .route(
+            "/apps/{id}/version-logs",
+            get(app_handler::list_app_version_logs),
+        )
+        .route(
+            "/apps/{id}/nodes/{nodeId}/variables",
+            get(app_handler::get_node_variables),
+        )
+}
+
+// Run routes
+fn run_routes() -> Router {
+    Router::new()
+        .route(
+            "/apps/{id}/versions/{version}/run",
+            post(run_handler::run_app_version),
+        )
+        .route(
+            "/apps/{id}/versions/{version}/run/stream",
+            post(run_handler::stream_app_version),
+        )
+        .route(
+            "/apps/{id}/nodes/{nodeId}/test",
+            post(run_handler::test_node),
+        )
+        .route("/runs", get(run_handler::list_runs))
+        .route("/runs/{id}", get(run_handler::get_run))
+        .route(
+            "/apps/{id}/debug-sessions",
+            post(debug_handler::create_debug_session),
+        )
+        .route(
+            "/debug-sessions/{id}",
+            get(debug_handler::get_debug_session),
+        )
+        .route(
+            "/debug-sessions/{id}",
+            delete(debug_handler::delete_debug_session),
+        )
+        .route(
+            "/debug-sessions/{id}/step",
+            post(debug_handler::step_debug_session),
+        )
+        .route(
+            "/debug-sessions/{id}/continue",
+            post(debug_handler::continue_debug_session),
+        )
+        .route(
+            "/debug-sessions/{id}/breakpoints",
+            put(debug_handler::update_debug_breakpoints),
+        )
+}
+
+// Datasource routes
+fn datasource_routes() -> Router {
+    Router::new()
+        .route(
+            "/datasource/connections",
+            get(datasource_handler::list_connections),
+        )
+        .route(
+            "/datasource/connections",
+            post(datasource_handler::create_connection),
+        )
+        .route(
+            "/datasource/connections/{id}",
+            get(datasource_handler::get_connection),
+        )
+        .route(
+            "/datasource/connections/{id}",
+            put(datasource_handler::update_connection),
+        )
+        .route(
+            "/datasource/connections/{id}",
+            delete(datasource_handler::delete_connection),
+        )
+        .route(
+            "/datasource/connections/test",
+            post(datasource_handler::test_connection),
+        )
+        .route(
+            "/datasource/connections/{id}/test",
+            post(datasource_handler::test_saved_connection),
+        )
+}
+
+// API key routes
+fn api_key_routes() -> Router {
+    Router::new()
+        .route("/api-keys", get(api_key_handler::list_api_keys))
+        .route("/api-keys", post(api_key_handler::create_api_key))
+        .route("/api-keys/{id}", delete(api_key_handler::delete_api_key))
+}
+
+// Published routes
+fn published_routes() -> Router {
+    Router::new().route("/run/{appId}", post(publish_handler::run_published))
 }
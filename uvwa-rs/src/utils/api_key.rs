@@ -0,0 +1,52 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+// 密钥前缀，便于在日志和配置中识别
+const PREFIX: &str = "uvwa-";
+const KEY_BYTES: usize = 32;
+// 列表中展示的密钥开头，用于区分同名的密钥
+const HINT_LEN: usize = 12;
+
+// API 密钥：只在创建时返回明文，保存的是 SHA-256 摘要，
+// 密钥本身是随机生成的高熵值，不需要加盐和慢哈希
+pub fn generate() -> String {
+    let mut bytes = [0u8; KEY_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{}{}", PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+pub fn hash(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+pub fn hint(key: &str) -> String {
+    let end = key
+        .char_indices()
+        .nth(HINT_LEN)
+        .map(|(i, _)| i)
+        .unwrap_or(key.len());
+    format!("{}...", &key[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key() {
+        let key = generate();
+        assert!(key.starts_with(PREFIX));
+        assert_eq!(key.len(), PREFIX.len() + 43);
+        assert_ne!(key, generate());
+
+        assert_eq!(
+            hash("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(hash(&key), hash(&key));
+        assert_eq!(hint(&key), format!("{}...", &key[..HINT_LEN]));
+        assert_eq!(hint("short"), "short...");
+    }
+}
@@ -1 +1,4 @@
+pub mod api_key;
 pub mod id;
+pub mod secret;
+pub mod semver;
@@ -0,0 +1,162 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::sync::OnceLock;
+
+// 密文前缀，没有前缀的值视为尚未加密的历史数据
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+static INSTANCE: OnceLock<Secret> = OnceLock::new();
+
+// 凭证加解密：AES-256-GCM，主密钥为 base64 编码的 32 字节
+// 轮换密钥时把新密钥配置为主密钥、旧密钥放入 previous_keys，
+// 旧密钥只用于解密，重新加密后即可移除
+pub struct Secret {
+    current: Aes256Gcm,
+    previous: Vec<Aes256Gcm>,
+}
+
+impl Secret {
+    pub fn new(master_key: &str, previous_keys: &[String]) -> anyhow::Result<Self> {
+        if master_key.trim().is_empty() {
+            bail!("secret master key is not configured, set SECRET_MASTER_KEY");
+        }
+        let previous = previous_keys
+            .iter()
+            .filter(|k| !k.trim().is_empty())
+            .map(|k| cipher(k))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self {
+            current: cipher(master_key)?,
+            previous,
+        })
+    }
+
+    // 启动时初始化全局实例
+    pub fn init(secret: Secret) {
+        let _ = INSTANCE.set(secret);
+    }
+
+    pub fn global() -> anyhow::Result<&'static Secret> {
+        INSTANCE
+            .get()
+            .ok_or_else(|| anyhow!("secret master key is not initialized"))
+    }
+
+    // 是否配置了待轮换的旧密钥
+    pub fn rotating(&self) -> bool {
+        !self.previous.is_empty()
+    }
+
+    pub fn encrypt(&self, plain: &str) -> anyhow::Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let data = self
+            .current
+            .encrypt(&nonce, plain.as_bytes())
+            .map_err(|_| anyhow!("failed to encrypt secret"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(data);
+        Ok(format!("{}{}", PREFIX, STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, value: &str) -> anyhow::Result<String> {
+        let Some(encoded) = value.strip_prefix(PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let payload = STANDARD.decode(encoded)?;
+        if payload.len() < NONCE_LEN {
+            bail!("malformed secret");
+        }
+        let (nonce, data) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::from_slice(nonce);
+
+        // 先用主密钥解密，失败时依次尝试旧密钥
+        for cipher in std::iter::once(&self.current).chain(&self.previous) {
+            if let Ok(plain) = cipher.decrypt(nonce, data) {
+                return Ok(String::from_utf8(plain)?);
+            }
+        }
+        bail!("secret cannot be decrypted with the configured keys")
+    }
+
+    // 轮换时重新加密：明文或旧密钥加密的值返回主密钥加密的新密文，已经是主密钥加密的返回 None
+    pub fn reencrypt(&self, value: &str) -> anyhow::Result<Option<String>> {
+        if self.is_current(value) {
+            return Ok(None);
+        }
+        let plain = self.decrypt(value)?;
+        self.encrypt(&plain).map(Some)
+    }
+
+    fn is_current(&self, value: &str) -> bool {
+        let Some(payload) = value
+            .strip_prefix(PREFIX)
+            .and_then(|v| STANDARD.decode(v).ok())
+        else {
+            return false;
+        };
+        payload.len() >= NONCE_LEN && {
+            let (nonce, data) = payload.split_at(NONCE_LEN);
+            self.current.decrypt(Nonce::from_slice(nonce), data).is_ok()
+        }
+    }
+}
+
+fn cipher(key: &str) -> anyhow::Result<Aes256Gcm> {
+    let bytes = STANDARD
+        .decode(key.trim())
+        .map_err(|_| anyhow!("secret key must be base64 encoded"))?;
+    Aes256Gcm::new_from_slice(&bytes).map_err(|_| anyhow!("secret key must be 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_A: &str = "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=";
+    const KEY_B: &str = "ZmVkY2JhOTg3NjU0MzIxMGZlZGNiYTk4NzY1NDMyMTA=";
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret = Secret::new(KEY_A, &[]).unwrap();
+        let encrypted = secret.encrypt("p@ssw0rd").unwrap();
+        assert!(encrypted.starts_with(PREFIX));
+        assert!(!encrypted.contains("p@ssw0rd"));
+        // 每次加密使用随机 nonce
+        assert_ne!(encrypted, secret.encrypt("p@ssw0rd").unwrap());
+        assert_eq!(secret.decrypt(&encrypted).unwrap(), "p@ssw0rd");
+        // 历史明文原样返回
+        assert_eq!(secret.decrypt("plain").unwrap(), "plain");
+
+        let other = Secret::new(KEY_B, &[]).unwrap();
+        assert!(other.decrypt(&encrypted).is_err());
+
+        assert!(Secret::new("", &[]).is_err());
+        assert!(Secret::new("not base64!", &[]).is_err());
+        assert!(Secret::new("c2hvcnQ=", &[]).is_err());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let old = Secret::new(KEY_A, &[]).unwrap();
+        let encrypted = old.encrypt("p@ssw0rd").unwrap();
+
+        let secret = Secret::new(KEY_B, &[KEY_A.to_string()]).unwrap();
+        assert!(secret.rotating());
+        assert_eq!(secret.decrypt(&encrypted).unwrap(), "p@ssw0rd");
+
+        let rotated = secret.reencrypt(&encrypted).unwrap().unwrap();
+        assert_eq!(secret.reencrypt(&rotated).unwrap(), None);
+        assert_eq!(
+            Secret::new(KEY_B, &[]).unwrap().decrypt(&rotated).unwrap(),
+            "p@ssw0rd"
+        );
+
+        let migrated = secret.reencrypt("plain").unwrap().unwrap();
+        assert_eq!(secret.decrypt(&migrated).unwrap(), "plain");
+    }
+}
@@ -0,0 +1,291 @@
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
+
+// 语义化版本号 major.minor.patch[-pre.release][+build]，按 SemVer 2.0 的优先级比较
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    // 构建信息不参与比较
+    pub build: Option<String>,
+}
+
+// 先行版本的标识符：纯数字按数值比较，且低于字母数字标识符
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+// 自动递增的级别
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+}
+
+impl Version {
+    pub const ZERO: Self = Self {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        pre: Vec::new(),
+        build: None,
+    };
+
+    // 严格解析，不接受前导零、空标识符和多余的段
+    pub fn parse(version: &str) -> Option<Self> {
+        let (rest, build) = match version.split_once('+') {
+            Some((rest, build)) => {
+                if !build.split('.').all(is_identifier) {
+                    return None;
+                }
+                (rest, Some(build.to_string()))
+            }
+            None => (version, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let mut parts = core.split('.');
+        let major = numeric(parts.next()?)?;
+        let minor = numeric(parts.next()?)?;
+        let patch = numeric(parts.next()?)?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(|id| {
+                    if id.bytes().all(|b| b.is_ascii_digit()) {
+                        numeric(id).map(Identifier::Numeric)
+                    } else {
+                        is_identifier(id).then(|| Identifier::Alpha(id.to_string()))
+                    }
+                })
+                .collect::<Option<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        Some(Self {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    // 先行版本号，例如 beta.1
+    pub fn pre_release(&self) -> Option<String> {
+        if self.pre.is_empty() {
+            return None;
+        }
+        let ids: Vec<_> = self.pre.iter().map(|id| id.to_string()).collect();
+        Some(ids.join("."))
+    }
+
+    // 计算下一个版本，与 npm version 的规则一致：
+    // 先行版本递增到对应级别时直接转为正式版本，例如 1.1.0-beta 递增 minor 得到 1.1.0
+    pub fn bump(&self, bump: Bump) -> Self {
+        let pre = !self.pre.is_empty();
+        let (mut major, mut minor, mut patch) = (self.major, self.minor, self.patch);
+        let mut ids = Vec::new();
+        match bump {
+            Bump::Major => {
+                if !(pre && minor == 0 && patch == 0) {
+                    major += 1;
+                }
+                (minor, patch) = (0, 0);
+            }
+            Bump::Minor => {
+                if !(pre && patch == 0) {
+                    minor += 1;
+                }
+                patch = 0;
+            }
+            Bump::Patch => {
+                if !pre {
+                    patch += 1;
+                }
+            }
+            // 递增最后一个数字标识符，没有时追加 0；正式版本先递增 patch
+            Bump::Prerelease => {
+                if pre {
+                    ids = self.pre.clone();
+                    let last = ids.iter_mut().rev().find_map(|id| match id {
+                        Identifier::Numeric(n) => Some(n),
+                        Identifier::Alpha(_) => None,
+                    });
+                    match last {
+                        Some(n) => *n += 1,
+                        None => ids.push(Identifier::Numeric(0)),
+                    }
+                } else {
+                    patch += 1;
+                    ids.push(Identifier::Numeric(0));
+                }
+            }
+        }
+        Self {
+            major,
+            minor,
+            patch,
+            pre: ids,
+            build: None,
+        }
+    }
+}
+
+fn numeric(part: &str) -> Option<u64> {
+    let leading_zero = part.len() > 1 && part.starts_with('0');
+    if part.is_empty() || leading_zero || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    part.parse().ok()
+}
+
+fn is_identifier(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // 正式版本高于同号的先行版本
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+// 优先级相同即视为相同版本，构建信息不同的版本不能重复发布
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Numeric(n) => write!(f, "{}", n),
+            Self::Alpha(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = self.pre_release() {
+            write!(f, "-{}", pre)?;
+        }
+        if let Some(build) = &self.build {
+            write!(f, "+{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let version = Version::parse("1.2.3-beta.11+build.5").unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 2, 3));
+        assert_eq!(version.pre_release().as_deref(), Some("beta.11"));
+        assert_eq!(version.build.as_deref(), Some("build.5"));
+        assert_eq!(version.to_string(), "1.2.3-beta.11+build.5");
+
+        for invalid in [
+            "",
+            "1",
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.x",
+            "1.2.3-",
+            "1.2.3-a..b",
+            "1.2.3-01",
+            "v1.2.3",
+            "1.2.3+",
+        ] {
+            assert!(Version::parse(invalid).is_none(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_precedence() {
+        // SemVer 2.0 规范中的示例顺序
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1",
+            "1.1.0",
+            "2.0.0",
+        ];
+        let versions: Vec<_> = ordered.iter().map(|v| Version::parse(v).unwrap()).collect();
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
+        }
+        assert_eq!(
+            Version::parse("1.0.0+a")
+                .unwrap()
+                .cmp(&Version::parse("1.0.0+b").unwrap()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_bump() {
+        let cases = [
+            ("1.2.3", Bump::Major, "2.0.0"),
+            ("1.2.3", Bump::Minor, "1.3.0"),
+            ("1.2.3", Bump::Patch, "1.2.4"),
+            ("1.2.3", Bump::Prerelease, "1.2.4-0"),
+            ("2.0.0-rc.1", Bump::Major, "2.0.0"),
+            ("1.2.0-rc.1", Bump::Major, "2.0.0"),
+            ("1.3.0-beta", Bump::Minor, "1.3.0"),
+            ("1.2.4-beta", Bump::Patch, "1.2.4"),
+            ("1.2.4-beta", Bump::Prerelease, "1.2.4-beta.0"),
+            ("1.2.4-beta.1", Bump::Prerelease, "1.2.4-beta.2"),
+            ("1.2.4-1.beta", Bump::Prerelease, "1.2.4-2.beta"),
+            ("1.2.3+build.1", Bump::Patch, "1.2.4"),
+        ];
+        for (version, bump, expected) in cases {
+            let next = Version::parse(version).unwrap().bump(bump);
+            assert_eq!(next.to_string(), expected, "{} {:?}", version, bump);
+            assert!(next > Version::parse(version).unwrap());
+        }
+    }
+}
@@ -8,9 +8,9 @@ use crate::web::error::WebError;
 use rust_i18n::t;
 use tracing::{debug, error};
 use uorm::error::DbError;
-use validator::ValidationErrors;
+use validator::{ValidationError, ValidationErrors};
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct R<T: Serialize> {
     pub code: i32,
     pub message: String,
@@ -36,6 +36,16 @@ impl<T: Serialize> R<T> {
         }
     }
 
+    // 返回错误的同时携带数据，例如草稿校验的问题列表
+    pub fn err_with_data(err: WebError, data: T) -> Self {
+        let (code, message) = map_err(err);
+        Self {
+            code,
+            message,
+            data: Some(data),
+        }
+    }
+
     pub fn from<E>(result: Result<T, E>) -> Self
     where
         WebError: From<E>,
@@ -115,6 +125,11 @@ fn format_validation_errors(err: &ValidationErrors) -> String {
     let mut msgs = Vec::new();
     for (field, errs) in err.field_errors() {
         for e in errs {
+            // 自定义提示优先，例如流程输入变量规则中配置的 message
+            if let Some(message) = &e.message {
+                msgs.push(format!("{}: {}", field, message));
+                continue;
+            }
             let detail = match e.code.as_ref() {
                 "required" => t!(Code::ValidationRequired.to_string()).to_string(),
                 "length" => {
@@ -152,9 +167,16 @@ fn format_validation_errors(err: &ValidationErrors) -> String {
                     }
                 }
                 "email" => t!(Code::ValidationEmail.to_string()).to_string(),
-                _ => e.message.clone().map(|m| m.to_string()).unwrap_or_else(|| {
-                    t!(Code::ValidationUnknown.to_string(), code => e.code).to_string()
-                }),
+                "enum" => {
+                    let options = param_text(e, "options").unwrap_or_default();
+                    t!(Code::ValidationEnum.to_string(), options => options).to_string()
+                }
+                "pattern" => t!(Code::ValidationPattern.to_string()).to_string(),
+                "type" => {
+                    let kind = param_text(e, "kind").unwrap_or_default();
+                    t!(Code::ValidationType.to_string(), kind => kind).to_string()
+                }
+                _ => t!(Code::ValidationUnknown.to_string(), code => e.code).to_string(),
             };
             msgs.push(format!("{}: {}", field, detail));
         }
@@ -166,7 +188,15 @@ fn format_validation_errors(err: &ValidationErrors) -> String {
     }
 }
 
-fn translate(code: i32, params: &Vec<(String, String)>) -> String {
+// 字符串参数去掉 JSON 引号
+fn param_text(e: &ValidationError, name: &str) -> Option<String> {
+    e.params.get(name).map(|v| match v {
+        serde_json::Value::String(s) => s.clone(),
+        v => v.to_string(),
+    })
+}
+
+pub fn translate(code: i32, params: &Vec<(String, String)>) -> String {
     let key = code.to_string();
     // 使用 t! 宏进行翻译，如果有参数则进行替换
     // 注意：rust-i18n 的 t! 宏在运行时替换可能需要不同的方式，这里保持手动替换逻辑作为后备或增强
@@ -196,7 +226,6 @@ macro_rules! r {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use validator::ValidationError;
 
     #[test]
     fn test_validation_messages() {
@@ -228,5 +257,20 @@ mod tests {
         rust_i18n::set_locale("zh");
         let msg_len_zh = format_validation_errors(&errs_len);
         assert!(msg_len_zh.contains("长度必须在 10 和 20 之间"));
+
+        // Test enum and custom message
+        let mut errs_enum = ValidationErrors::new();
+        let mut err_enum = ValidationError::new("enum");
+        err_enum.add_param(std::borrow::Cow::from("options"), &"a, b");
+        errs_enum.add("field3", err_enum);
+        errs_enum.add(
+            "field4",
+            ValidationError::new("pattern").with_message(std::borrow::Cow::from("bad code")),
+        );
+
+        rust_i18n::set_locale("en");
+        let msg_enum = format_validation_errors(&errs_enum);
+        assert!(msg_enum.contains("field3: must be one of a, b"));
+        assert!(msg_enum.contains("field4: bad code"));
     }
 }
@@ -1,4 +1,5 @@
 use std::future::Future;
+use std::sync::LazyLock;
 
 use crate::web::error::WebError;
 use crate::web::middleware::fallback;
@@ -8,8 +9,19 @@ use axum::middleware::{Next, from_fn};
 use axum::response::Response;
 use axum::{Router, middleware};
 use tokio::signal;
+use tokio::sync::watch;
 use tracing::{error, info};
 
+// 开始优雅关闭时置为 true。优雅关闭会等待所有连接结束，
+// SSE 等长连接需要监听它主动结束，否则服务无法退出
+static SHUTDOWN: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::channel(false).0);
+
+// 等待服务开始关闭
+pub async fn shutdown_requested() {
+    let mut receiver = SHUTDOWN.subscribe();
+    let _ = receiver.wait_for(|closing| *closing).await;
+}
+
 pub struct WebServer {
     router: Router,
     addr: String,
@@ -61,7 +73,10 @@ impl WebServer {
         let listener = tokio::net::TcpListener::bind(&self.addr).await?;
 
         // 优雅关闭处理
-        let server = axum::serve(listener, self.router).with_graceful_shutdown(wait_for_shutdown());
+        let server = axum::serve(listener, self.router).with_graceful_shutdown(async {
+            wait_for_shutdown().await;
+            SHUTDOWN.send_replace(true);
+        });
         if let Err(e) = server.await {
             error!("Server error: {}", e);
             return Err(WebError::Sys(format!("Server error: {}", e)));
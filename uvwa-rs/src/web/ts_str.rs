@@ -49,6 +49,44 @@ where
     }
 }
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StrOrNumber {
+    Str(String),
+    Num(serde_json::Number),
+}
+
+// 兼容前端 `<Input type="number">` 提交的字符串数字与 JSON 数字
+pub fn option_str_or_number<'de, T, D>(d: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let opt = Option::<StrOrNumber>::deserialize(d)?;
+    let s = match opt {
+        None => return Ok(None),
+        Some(StrOrNumber::Str(s)) => s,
+        Some(StrOrNumber::Num(n)) => n.to_string(),
+    };
+    match s.trim() {
+        "" | "null" => Ok(None),
+        s => s.parse::<T>().map(Some).map_err(serde::de::Error::custom),
+    }
+}
+
+// 兼容前端下拉框未选择时提交的空字符串
+pub fn option_non_empty<'de, T, D>(d: D) -> Result<Option<T>, D::Error>
+where
+    T: serde::de::DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    match Option::<serde_json::Value>::deserialize(d)? {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(s)) if s.is_empty() => Ok(None),
+        Some(v) => T::deserialize(v).map(Some).map_err(serde::de::Error::custom),
+    }
+}
 
 pub fn vec_to_number<'de, T, D>(d: D) -> Result<Vec<T>, D::Error>
 where
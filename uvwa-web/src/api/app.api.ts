@@ -1,4 +1,4 @@
-import { App, CreateAppReq, ListAppReq, UpdateAppReq } from '@/types/app.types';
+import { App, CreateAppReq, ListAppReq, ScopeVariable, UpdateAppReq } from '@/types/app.types';
 import { R } from '@/types/common.types'; // 获取单个工作流详情
 import request from '@/utils/request';
 
@@ -12,6 +12,11 @@ export async function updateAppSpec(id: string, spec: string): Promise<R<void>>
   return request.put(`/apps/${id}/spec`, { spec });
 }
 
+// 获取草稿中节点可引用的变量
+export async function getNodeVariables(id: string, nodeId: string): Promise<R<ScopeVariable[]>> {
+  return request.get(`/apps/${id}/nodes/${nodeId}/variables`);
+}
+
 // 获取工作流列表
 export async function listApps(params?: ListAppReq): Promise<R<App[]>> {
   return request.get('/apps', params);
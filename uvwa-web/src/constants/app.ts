@@ -15,6 +15,8 @@ export const DATABASE_TYPES = [
 export const COMPARE_OPERATOR_TYPES = [
   { value: 'in', label: '包含' },
   { value: 'not in', label: '不包含' },
+  { value: 'one of', label: '属于' },
+  { value: 'not one of', label: '不属于' },
   { value: '=', label: '等于' },
   { value: '>', label: '大于' },
   { value: '<', label: '小于' },
@@ -25,6 +27,7 @@ export const COMPARE_OPERATOR_TYPES = [
   { value: 'end with', label: '结尾为' },
   { value: 'is empty', label: '为空' },
   { value: 'is not empty', label: '不为空' },
+  { value: 'regex', label: '匹配正则' },
 ];
 
 // 校验规则类型配置
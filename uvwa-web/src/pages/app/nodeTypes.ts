@@ -16,6 +16,7 @@ import EditSqlTransactionAttribute from '@/pages/app/workflow/components/nodes/F
 import FlowStartNode from '@/pages/app/workflow/components/nodes/FlowStartNode';
 import EditStartAttribute from '@/pages/app/workflow/components/nodes/FlowStartNode/EditStartAttribute';
 import {
+  AssignNode,
   CaseNode,
   CodeNode,
   EndNode,
@@ -230,8 +231,8 @@ export const NodeDefineTypes: ObjectType<FlowNodeDefine> = {
       position: { x: 0, y: 0 },
       data: {
         title: '变量赋值',
-        group: false,
+        items: [],
       },
-    } as FlowNode<any>,
+    } as FlowNode<AssignNode>,
   },
 };
@@ -38,7 +38,7 @@ export default ({ node, onChange }: SqlNodeProps) => {
     const updatedData: SqlNode = {
       ...node.data,
       input: allValues.input || [],
-      spec: allValues.content || '',
+      content: allValues.content || '',
       output: [
         {
           id: 'rows',
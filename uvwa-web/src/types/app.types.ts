@@ -66,6 +66,19 @@ export interface Variable {
   rules?: Rule[];
 }
 
+/**
+ * 节点可引用的变量，由服务端按连线和容器计算
+ */
+export interface ScopeVariable {
+  varId: string;
+  varName: string;
+  type?: VariableKind;
+  // 会话变量没有所属节点
+  nodeId?: string;
+  nodeType?: string;
+  nodeName?: string;
+}
+
 /**
  * 节点类型
  */
@@ -208,10 +221,28 @@ export interface SubFlowNode {
   title?: string;
   description?: string;
   flowId: string;
+  version?: string;
   input?: Variable[];
   output?: Variable[];
 }
 
+export interface AssignItem {
+  // 流程变量为变量 id，会话变量为变量名
+  scope?: 'workflow' | 'conversation';
+  target: string;
+  mode?: 'set' | 'append';
+  source:
+    | { type: 'literal'; value: any }
+    | { type: 'reference'; value: string }
+    | { type: 'expression'; value: string };
+}
+
+export interface AssignNode {
+  title?: string;
+  description?: string;
+  items?: AssignItem[];
+}
+
 export interface NoteNode {
   title?: string;
   description?: string;
@@ -236,6 +267,8 @@ export interface AppDraft extends App {
     y: number;
     zoom: number;
   };
+  // 对话应用的会话变量
+  conversationVariables?: Variable[];
 }
 
 export interface CreateAppReq {